@@ -0,0 +1,268 @@
+//! File cache for the course and organization listings fetched from the server
+
+use anyhow::Context;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Mutex, time::Duration};
+
+/// How long a cached listing is used before it is fetched again
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Key of the course listing of an organization
+pub fn courses_key(org: &str) -> String {
+    format!("courses-{org}")
+}
+
+/// Key of the exercise listing of a course
+pub fn course_exercises_key(course_id: u32) -> String {
+    format!("course-{course_id}-exercises")
+}
+
+/// Key of the details of a course
+pub fn course_details_key(course_id: u32) -> String {
+    format!("course-{course_id}-details")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Fresh entries are used, stale and missing ones are fetched from the server
+    Normal,
+    /// Entries are used regardless of their age and the server is never contacted
+    Offline,
+    /// The server is always contacted and the entries are replaced
+    Refresh,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// Unix timestamp of when the data was fetched
+    fetched_at: i64,
+    data: T,
+}
+
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    mode: CacheMode,
+    /// When the oldest entry served in offline mode was fetched
+    oldest_offline_entry: Mutex<Option<i64>>,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, mode: CacheMode) -> Self {
+        Self {
+            dir,
+            ttl: DEFAULT_TTL,
+            mode,
+            oldest_offline_entry: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached value for the key, or calls fetch and caches its result.
+    /// In offline mode fetch is never called.
+    pub fn get_or_fetch<T, F>(&self, key: &str, fetch: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> anyhow::Result<T>,
+    {
+        self.get_or_fetch_at(key, fetch, Utc::now())
+    }
+
+    /// Calls fetch and caches its result, the cached value is only used in offline mode.
+    /// For data that changes with the actions of the user, like the points awarded to them,
+    /// which would otherwise be shown out of date after a submission.
+    pub fn fetch_or_get_offline<T, F>(&self, key: &str, fetch: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> anyhow::Result<T>,
+    {
+        self.get_or_fetch_with_ttl_at(key, fetch, Utc::now(), Duration::ZERO)
+    }
+
+    fn get_or_fetch_at<T, F>(&self, key: &str, fetch: F, now: DateTime<Utc>) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> anyhow::Result<T>,
+    {
+        self.get_or_fetch_with_ttl_at(key, fetch, now, self.ttl)
+    }
+
+    fn get_or_fetch_with_ttl_at<T, F>(
+        &self,
+        key: &str,
+        fetch: F,
+        now: DateTime<Utc>,
+        ttl: Duration,
+    ) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> anyhow::Result<T>,
+    {
+        match self.mode {
+            CacheMode::Offline => {
+                let entry = self.read::<T>(key).with_context(|| {
+                    format!("No cached data for '{key}'. Run the command once without --offline to cache it")
+                })?;
+                let mut oldest = self
+                    .oldest_offline_entry
+                    .lock()
+                    .expect("the lock should not be poisoned");
+                if oldest.map_or(true, |oldest| entry.fetched_at < oldest) {
+                    *oldest = Some(entry.fetched_at);
+                }
+                return Ok(entry.data);
+            }
+            CacheMode::Normal => {
+                if let Some(entry) = self.read::<T>(key) {
+                    let age = now.timestamp() - entry.fetched_at;
+                    if (0..ttl.as_secs() as i64).contains(&age) {
+                        return Ok(entry.data);
+                    }
+                }
+            }
+            CacheMode::Refresh => {}
+        }
+
+        let entry = Entry {
+            fetched_at: now.timestamp(),
+            data: fetch()?,
+        };
+        if let Err(err) = self.write(key, &entry) {
+            log::warn!("Failed to cache '{key}': {err:#}");
+        }
+        Ok(entry.data)
+    }
+
+    /// Returns the cached value for the key regardless of its age, never contacts the server
+    pub fn peek<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.read(key).map(|entry| entry.data)
+    }
+
+    /// When the oldest cached entry served in offline mode was fetched, if any were served
+    pub fn offline_data_fetched_at(&self) -> Option<DateTime<Utc>> {
+        let oldest = *self
+            .oldest_offline_entry
+            .lock()
+            .expect("the lock should not be poisoned");
+        oldest.and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+    }
+
+    /// Removes all cached entries
+    pub fn clear(&self) -> anyhow::Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("Failed to remove cache at {}", self.dir.display()))?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Missing and unreadable entries are both treated as not cached
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<Entry<T>> {
+        let contents = fs::read(self.path(key)).ok()?;
+        match serde_json::from_slice(&contents) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                log::warn!("Ignoring invalid cache entry '{key}': {err}");
+                None
+            }
+        }
+    }
+
+    fn write<T: Serialize>(&self, key: &str, entry: &Entry<T>) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        fs::write(&path, serde_json::to_vec(entry)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn get_or_fetch_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let fetches = Cell::new(0);
+        let fetch = |value: u32| {
+            fetches.set(fetches.get() + 1);
+            anyhow::Ok(value)
+        };
+
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Normal);
+        assert_eq!(cache.get_or_fetch_at("key", || fetch(1), now).unwrap(), 1);
+        // fresh entries are used
+        let later = now + chrono::Duration::minutes(30);
+        assert_eq!(cache.get_or_fetch_at("key", || fetch(2), later).unwrap(), 1);
+        assert_eq!(fetches.get(), 1);
+        // stale entries are fetched again
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(cache.get_or_fetch_at("key", || fetch(3), later).unwrap(), 3);
+        assert_eq!(fetches.get(), 2);
+
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Refresh);
+        assert_eq!(cache.get_or_fetch_at("key", || fetch(4), later).unwrap(), 4);
+        assert_eq!(fetches.get(), 3);
+
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Offline);
+        let much_later = now + chrono::Duration::days(30);
+        assert_eq!(
+            cache
+                .get_or_fetch_at("key", || fetch(5), much_later)
+                .unwrap(),
+            4
+        );
+        assert!(cache
+            .get_or_fetch_at("missing", || fetch(6), much_later)
+            .is_err());
+        assert_eq!(fetches.get(), 3);
+        assert_eq!(cache.offline_data_fetched_at(), Some(later));
+    }
+
+    #[test]
+    fn fetch_or_get_offline_test() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Normal);
+        assert_eq!(
+            cache.fetch_or_get_offline("key", || anyhow::Ok(1)).unwrap(),
+            1
+        );
+        // fresh entries are fetched again
+        assert_eq!(
+            cache.fetch_or_get_offline("key", || anyhow::Ok(2)).unwrap(),
+            2
+        );
+
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Offline);
+        assert_eq!(
+            cache
+                .fetch_or_get_offline::<u32, _>("key", || anyhow::bail!("no connection"))
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn failed_fetch_is_not_cached_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf(), CacheMode::Normal);
+
+        assert!(cache
+            .get_or_fetch::<u32, _>("key", || anyhow::bail!("no connection"))
+            .is_err());
+        assert_eq!(cache.get_or_fetch("key", || anyhow::Ok(1)).unwrap(), 1);
+
+        assert_eq!(cache.peek::<u32>("key"), Some(1));
+        assert_eq!(cache.peek::<u32>("missing"), None);
+
+        cache.clear().unwrap();
+        assert!(!dir.path().exists());
+    }
+}
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -20,6 +21,22 @@ pub struct Cli {
     #[arg(short = 'u', long, hide = !cfg!(windows))]
     pub force_update: bool,
 
+    /// Output format. With `json`, the result of the command is also printed to stdout as JSON.
+    #[arg(long, value_enum, global = true, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Use a saved server profile for this command instead of the active one.
+    #[arg(long, global = true, value_name = "name")]
+    pub profile: Option<String>,
+
+    /// Show course and organization listings from the local cache without contacting the server.
+    #[arg(long, global = true, conflicts_with = "refresh")]
+    pub offline: bool,
+
+    /// Fetch course and organization listings from the server even if they are cached.
+    #[arg(long, global = true)]
+    pub refresh: bool,
+
     /// Only for internal testing, disables server connection.
     #[arg(long, hide = true)]
     pub testmode: bool,
@@ -28,8 +45,21 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Command {
     // tmc commands
+    /// Remove build artifacts from exercises.
+    Clean {
+        exercise: Option<String>,
+        /// Clean every downloaded exercise of this course.
+        #[arg(short, long, value_name = "course name", conflicts_with = "exercise")]
+        course: Option<String>,
+    },
     /// List the available courses.
     Courses,
+    /// Show the upcoming deadlines of the downloaded courses.
+    Deadlines {
+        /// Export every deadline and soft deadline to an iCalendar file instead.
+        #[arg(long, value_name = "file")]
+        ics: Option<PathBuf>,
+    },
     /// Download exercises for a course.
     Download {
         /// If set, the exercises of this course are downloaded. If not set, the selection is done from an interactive menu.
@@ -38,6 +68,18 @@ pub enum Command {
         /// If set, exercises are downloaded to the current working directory.
         #[arg(short = 'd', long)]
         currentdir: bool,
+        /// Only download exercises whose name matches this pattern. `*` matches any text and `?` any character.
+        #[arg(short, long, value_name = "pattern")]
+        exercise: Option<String>,
+        /// Only download exercises of this part of the course, e.g. 2 for part02-01_example.
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Only download exercises with a deadline before this date (YYYY-MM-DD) or time (RFC 3339).
+        #[arg(long, value_name = "date")]
+        due_before: Option<String>,
+        /// Choose the exercises to download from an interactive menu.
+        #[arg(short, long)]
+        select: bool,
     },
     /// List the exercises for a specific course.
     Exercises {
@@ -49,6 +91,22 @@ pub enum Command {
         /// Initiates the non-interactive mode.
         #[arg(short, long)]
         non_interactive: bool,
+        /// Email or username. Can also be set with the TMC_USERNAME environment variable.
+        #[arg(short, long)]
+        username: Option<String>,
+        /// Read the password from the first line of stdin. Otherwise the password is taken
+        /// from the TMC_PASSWORD environment variable or asked.
+        #[arg(long)]
+        password_stdin: bool,
+        /// Log in with an existing OAuth access token instead of a password.
+        #[arg(long, conflicts_with_all = ["username", "password_stdin"])]
+        token: Option<String>,
+        /// Read the access token from the first line of stdin.
+        #[arg(long, conflicts_with_all = ["token", "username", "password_stdin"])]
+        token_stdin: bool,
+        /// Select the organization with this slug without asking. No courses are downloaded.
+        #[arg(short, long, value_name = "slug")]
+        organization: Option<String>,
     },
     /// Logout from TMC server.
     Logout,
@@ -60,16 +118,72 @@ pub enum Command {
     },
     /// Submit exercise to TMC pastebin.
     Paste { exercise: Option<String> },
+    /// Manage server profiles.
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+    /// Restore an exercise to its original template. The current files are backed up first.
+    Reset {
+        exercise: Option<String>,
+        /// Skip the confirmation prompt.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Download the model solution of an exercise next to the exercise.
+    Solution { exercise: Option<String> },
+    /// Show the progress of the downloaded courses.
+    Status {
+        /// If set, only the status of this course is shown.
+        course: Option<String>,
+    },
+    /// List your previous submissions for an exercise or a course.
+    Submissions {
+        exercise: Option<String>,
+        /// List the submissions of every exercise of this course.
+        #[arg(short, long, value_name = "course name", conflicts_with = "exercise")]
+        course: Option<String>,
+    },
     /// Submit exercises to TMC server.
-    Submit { exercise: Option<String> },
+    Submit {
+        exercise: Option<String>,
+        /// Run the local tests of every downloaded exercise of a course and submit the ones that pass.
+        #[arg(short, long, conflicts_with = "exercise")]
+        passing: bool,
+        /// Course to submit with --passing. If not set, the course of the current directory is used.
+        #[arg(short, long, value_name = "course name", requires = "passing")]
+        course: Option<String>,
+    },
     /// Run local exercise tests.
-    Test { exercise: Option<String> },
+    Test {
+        exercise: Option<String>,
+        /// Run the tests of every downloaded exercise of a course.
+        #[arg(short, long, conflicts_with = "exercise")]
+        all: bool,
+        /// Course to test with --all. If not set, the course of the current directory is used.
+        #[arg(short, long, value_name = "course name", requires = "all")]
+        course: Option<String>,
+        /// Run the tests again whenever the files of the exercise change.
+        #[arg(short, long, conflicts_with = "all")]
+        watch: bool,
+        /// Write the test results as a report, "junit" or "tap". The report is written to stdout
+        /// unless a file is given, for example junit=results.xml. Can be given multiple times.
+        #[arg(
+            long,
+            value_name = "format[=file]",
+            value_parser = parse_report,
+            conflicts_with = "watch"
+        )]
+        report: Vec<Report>,
+    },
     /// Updates course exercises.
     Update {
         /// If set, exercises in the current working directory are updated.
         #[arg(short = 'd', long)]
         currentdir: bool,
     },
+    /// Show the logged in account, the server and organization in use and the login status.
+    Whoami,
 
     // hidden commands
     /// Finishes the autoupdater. Administator rights needed.
@@ -95,10 +209,72 @@ pub enum Command {
 
 impl Command {
     pub fn requires_organization_set(&self) -> bool {
-        matches!(self, Command::Download { .. } | Command::Courses { .. })
+        matches!(
+            self,
+            Command::Download { .. }
+                | Command::Courses { .. }
+                | Command::Deadlines { .. }
+                | Command::Solution { .. }
+                | Command::Status { .. }
+        )
     }
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// Save a new server profile.
+    Add {
+        name: String,
+        /// Root URL of the TMC server, for example https://tmc.mooc.fi.
+        #[arg(long, value_name = "url")]
+        root_url: String,
+        /// Organization to use with the profile.
+        #[arg(long, value_name = "slug")]
+        organization: Option<String>,
+    },
+    /// List the saved profiles.
+    List,
+    /// Use a profile by default. The name "default" returns to the built-in server.
+    Use { name: String },
+    /// Remove a profile and its login.
+    Remove { name: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub format: ReportFormat,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+}
+
+fn parse_report(arg: &str) -> Result<Report, String> {
+    let (format, path) = match arg.split_once('=') {
+        Some((format, path)) => (format, Some(PathBuf::from(path))),
+        None => (arg, None),
+    };
+    let format = match format {
+        "junit" => ReportFormat::Junit,
+        "tap" => ReportFormat::Tap,
+        _ => {
+            return Err(format!(
+                "unknown report format '{format}', expected junit or tap"
+            ))
+        }
+    };
+    Ok(Report { format, path })
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum ShellArg {
     Bash,
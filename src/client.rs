@@ -1,11 +1,20 @@
-use crate::{commands::util, config::TmcCliConfig, PLUGIN, PLUGIN_VERSION};
+use crate::{
+    cache::{self, Cache},
+    commands::util,
+    config::TmcCliConfig,
+    error::{self, CliError},
+    PLUGIN, PLUGIN_VERSION,
+};
 use anyhow::Context;
-use reqwest::Url;
+use reqwest::{StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::path::Path;
 use tmc_langs::{
     tmc::{
+        oauth2::{basic::BasicTokenType, AccessToken, EmptyExtraTokenFields, TokenResponse},
         response::{
-            Course, CourseDetails, CourseExercise, NewSubmission, Organization, SubmissionFinished,
+            Course, CourseDetails, CourseExercise, NewSubmission, Organization, Submission,
+            SubmissionFinished, SubmissionStatus,
         },
         TestMyCodeClient, TestMyCodeClientError, Token,
     },
@@ -13,27 +22,91 @@ use tmc_langs::{
 };
 
 pub const SUCCESSFUL_LOGIN: &str = "Logged in successfully!";
-pub const WRONG_LOGIN: &str = "Wrong username or password";
+
+/// The account the client is logged in as
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct User {
+    pub id: u32,
+    pub username: String,
+    pub email: String,
+}
 
 pub struct Client {
     pub tmc_client: TestMyCodeClient,
     pub test_mode: bool,
+    root_url: Url,
+    /// Name under which the credentials are stored, differs between profiles
+    credentials_name: String,
+    /// The token of the logged in user, set by load_login and login_with_token
+    token: Option<Token>,
+    /// Cache for the listings fetched from the server, none if caching is not used
+    cache: Option<Cache>,
 }
 
 impl Client {
-    pub fn new(tmc_root_url: Url, test_mode: bool) -> anyhow::Result<Self> {
-        let (tmc_client, _credentials) = tmc_langs::init_testmycode_client_with_credentials(
-            tmc_root_url,
-            PLUGIN,
-            PLUGIN_VERSION,
+    pub fn new(
+        tmc_root_url: Url,
+        credentials_name: String,
+        test_mode: bool,
+    ) -> anyhow::Result<Self> {
+        // only the credentials of the profile in use are loaded, so that the token
+        // of another profile is never sent to this profile's server
+        let mut tmc_client = TestMyCodeClient::new(
+            tmc_root_url.clone(),
+            PLUGIN.to_string(),
+            PLUGIN_VERSION.to_string(),
         )?;
+        if !test_mode {
+            if let Some(credentials) = util::get_credentials(&credentials_name) {
+                tmc_client.set_token(credentials.token());
+            }
+        }
 
         Ok(Client {
             tmc_client,
             test_mode,
+            root_url: tmc_root_url,
+            credentials_name,
+            token: None,
+            cache: None,
         })
     }
 
+    pub fn set_cache(&mut self, cache: Cache) {
+        self.cache = Some(cache);
+    }
+
+    /// When the oldest cached listing shown in offline mode was fetched
+    pub fn offline_data_fetched_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.cache
+            .as_ref()
+            .and_then(|cache| cache.offline_data_fetched_at())
+    }
+
+    /// Goes through the cache if one is set
+    fn cached<T, F>(&self, key: &str, fetch: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(&Self) -> anyhow::Result<T>,
+    {
+        match &self.cache {
+            Some(cache) => cache.get_or_fetch(key, || fetch(self)),
+            None => fetch(self),
+        }
+    }
+
+    /// Like `cached`, for data with the progress of the user that is only cached for offline use
+    fn cached_progress<T, F>(&self, key: &str, fetch: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(&Self) -> anyhow::Result<T>,
+    {
+        match &self.cache {
+            Some(cache) => cache.fetch_or_get_offline(key, || fetch(self)),
+            None => fetch(self),
+        }
+    }
+
     pub fn is_test_mode(&mut self) -> bool {
         self.test_mode
     }
@@ -42,18 +115,22 @@ impl Client {
         // match self.tmc_client.authenticate(PLUGIN, username, password) {
         match tmc_langs::login_with_password(&mut self.tmc_client, PLUGIN, username, password) {
             Ok(x) => Ok(x),
-            Err(x) => anyhow::bail!(Client::explain_login_fail(x)),
+            Err(x) => Err(Client::explain_login_fail(x)),
         }
     }
 
-    pub fn explain_login_fail(error: LangsError) -> String {
-        let res = format!("{error:?}");
-
-        if res.contains("The provided authorization grant is invalid, expired, revoked, does not match the redirection URI used in the authorization request, or was issued to another client.") {
-            return WRONG_LOGIN.to_string();
+    /// The server rejects wrong credentials in the token exchange with 400 Bad Request
+    /// or 401 Unauthorized, other failures keep their own kind, e.g. network errors.
+    pub fn explain_login_fail(error: LangsError) -> anyhow::Error {
+        match error {
+            LangsError::TestMyCodeClient(TestMyCodeClientError::HttpError { status, .. })
+                if matches!(status.as_u16(), 400 | 401) =>
+            {
+                CliError::WrongLogin.into()
+            }
+            LangsError::TestMyCodeClient(err) => CliError::from_client_error(err),
+            err => anyhow::Error::new(err).context("Login failed"),
         }
-
-        "Login failed with an unknown error message".to_string()
     }
 
     // tmc commands
@@ -64,29 +141,19 @@ impl Client {
         exercise_slug: &str,
         paste_message: Option<String>,
         locale: Option<Language>,
-    ) -> Result<NewSubmission, String> {
+    ) -> anyhow::Result<NewSubmission> {
         if self.test_mode {
-            return Err("Integration test input not yet implemented for paste command".to_string());
+            anyhow::bail!("Integration test input not yet implemented for paste command");
         }
-        match tmc_langs::paste_exercise(
+        tmc_langs::paste_exercise(
             &self.tmc_client,
             projects_dir,
             course_slug,
             exercise_slug,
             paste_message,
             locale,
-        ) {
-            Err(client_error) => match client_error {
-                LangsError::TestMyCodeClient(TestMyCodeClientError::HttpError { status, error, .. }) => {
-                    Err(format!("Status {status}, message: {error}"))
-                }
-                _ => Err(
-                    "Received unhandled TestMyCodeClientError when calling paste command from tmc_client"
-                        .to_string(),
-                ),
-            },
-            Ok(submission) => Ok(submission),
-        }
+        )
+        .map_err(error::from_langs_error)
     }
 
     pub fn load_login(&mut self, config: &TmcCliConfig) -> anyhow::Result<()> {
@@ -99,15 +166,16 @@ impl Client {
             if test_login_exists {
                 return Ok(());
             } else {
-                anyhow::bail!("No login found".to_string());
+                return Err(CliError::NotLoggedIn.into());
             }
         }
 
-        if let Some(credentials) = util::get_credentials() {
+        if let Some(credentials) = util::get_credentials(&self.credentials_name) {
             self.tmc_client.set_token(credentials.token());
+            self.token = Some(credentials.token());
             Ok(())
         } else {
-            anyhow::bail!("No login found. You need to be logged in to use this command");
+            Err(CliError::NotLoggedIn.into())
         }
     }
 
@@ -127,25 +195,101 @@ impl Client {
 
                 return Ok(SUCCESSFUL_LOGIN.to_string());
             }
-            anyhow::bail!(WRONG_LOGIN);
+            return Err(CliError::WrongLogin.into());
         }
 
         let token = self.authenticate(username, password)?;
-        if Credentials::save(PLUGIN, token).is_ok() {
+        if Credentials::save(&self.credentials_name, token).is_ok() {
             return Ok(SUCCESSFUL_LOGIN.to_string());
         };
 
         anyhow::bail!("Error! Saving credentials failed")
     }
 
+    /// Logs in with an existing OAuth access token.
+    /// The token is saved only if the server accepts it.
+    pub fn login_with_token(
+        &mut self,
+        access_token: String,
+        config: &mut TmcCliConfig,
+    ) -> anyhow::Result<String> {
+        if access_token.is_empty() {
+            anyhow::bail!("Token cannot be empty!");
+        }
+        if self.test_mode {
+            if access_token == "testtoken" {
+                config.set_test_login();
+                config.save().context("Problem saving login")?;
+                return Ok(SUCCESSFUL_LOGIN.to_string());
+            }
+            return Err(CliError::InvalidToken.into());
+        }
+
+        let token = Token::new(
+            AccessToken::new(access_token),
+            BasicTokenType::Bearer,
+            EmptyExtraTokenFields {},
+        );
+        self.token = Some(token.clone());
+        self.get_current_user()?.ok_or(CliError::InvalidToken)?;
+
+        self.tmc_client.set_token(token.clone());
+        if Credentials::save(&self.credentials_name, token).is_ok() {
+            return Ok(SUCCESSFUL_LOGIN.to_string());
+        };
+
+        anyhow::bail!("Error! Saving credentials failed")
+    }
+
+    /// Fetches the account of the logged in user, which also checks that the token is still valid.
+    /// Returns None if the server does not accept the token.
+    pub fn get_current_user(&self) -> anyhow::Result<Option<User>> {
+        if self.test_mode {
+            return Ok(Some(User {
+                id: 0,
+                username: "testusername".to_string(),
+                email: "testusername@example.com".to_string(),
+            }));
+        }
+
+        let token = self.token.as_ref().context("No login found")?;
+        let response = reqwest::blocking::Client::new()
+            .get(format!(
+                "{}/api/v8/users/current?show_user_fields=true",
+                self.root_url.as_str().trim_end_matches('/')
+            ))
+            .bearer_auth(token.access_token().secret())
+            .send()
+            .map_err(|err| CliError::Network(err.to_string()))?;
+        match response.status() {
+            status if status.is_success() => response
+                .json()
+                .map(Some)
+                .context("Failed to parse the user information from the server"),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Ok(None),
+            status => Err(CliError::ServerError(format!(
+                "Unexpected response from the server: {status}"
+            ))
+            .into()),
+        }
+    }
+
+    pub fn root_url(&self) -> &Url {
+        &self.root_url
+    }
+
     pub fn list_courses(&mut self, org: &str) -> anyhow::Result<Vec<Course>> {
+        self.cached(&cache::courses_key(org), |client| client.fetch_courses(org))
+    }
+
+    fn fetch_courses(&self, org: &str) -> anyhow::Result<Vec<Course>> {
         if self.test_mode {
             return Ok(vec![
                 Course {
                     name: "test-tmc-test-course".to_string(),
                     id: 0,
 
-                    title: "".to_string(),
+                    title: "Test TMC test course".to_string(),
                     description: None,
                     details_url: "".to_string(),
                     unlock_url: "".to_string(),
@@ -157,7 +301,7 @@ impl Client {
                     name: "imaginary-test-course".to_string(),
                     id: 1,
 
-                    title: "".to_string(),
+                    title: "Imaginary test course".to_string(),
                     description: None,
                     details_url: "".to_string(),
                     unlock_url: "".to_string(),
@@ -176,7 +320,7 @@ impl Client {
                         name: course.name,
                         id: course.id,
 
-                        title: "".to_string(),
+                        title: course.title,
                         description: None,
                         details_url: "".to_string(),
                         unlock_url: "".to_string(),
@@ -187,14 +331,15 @@ impl Client {
                 }
                 Ok(course_list)
             }
-            Err(TestMyCodeClientError::NotAuthenticated) => {
-                anyhow::bail!("Login token is invalid. Please try logging in again.")
-            }
-            Err(err) => anyhow::bail!("Unexpected error: '{err}'."),
+            Err(err) => Err(CliError::from_client_error(err)),
         }
     }
 
     pub fn get_organizations(&mut self) -> anyhow::Result<Vec<Organization>> {
+        self.cached("organizations", Self::fetch_organizations)
+    }
+
+    fn fetch_organizations(&self) -> anyhow::Result<Vec<Organization>> {
         if self.test_mode {
             return Ok(vec![
                 Organization {
@@ -223,6 +368,10 @@ impl Client {
     }
 
     pub fn logout(&mut self, config: &mut TmcCliConfig) -> anyhow::Result<()> {
+        // The cached listings contain the points of the user
+        if let Some(cache) = &self.cache {
+            cache.clear()?;
+        }
         if self.test_mode {
             // Remove test login from config file
             config.remove_test_login();
@@ -232,16 +381,43 @@ impl Client {
             return Ok(());
         }
 
-        let credentials = util::get_credentials().context("Failed to get credentials")?;
+        let credentials =
+            util::get_credentials(&self.credentials_name).context("Failed to get credentials")?;
         credentials.remove()?;
         Ok(())
     }
 
-    pub fn wait_for_submission(
-        &self,
-        submission_url: Url,
-    ) -> Result<SubmissionFinished, TestMyCodeClientError> {
-        self.tmc_client.wait_for_submission_at(submission_url)
+    pub fn wait_for_submission(&self, submission_url: Url) -> anyhow::Result<SubmissionFinished> {
+        if self.test_mode {
+            return Ok(SubmissionFinished {
+                api_version: 7,
+                all_tests_passed: Some(true),
+                user_id: 0,
+                login: "testusername".to_string(),
+                course: "test-tmc-test-course".to_string(),
+                exercise_name: "Imaginary test exercise".to_string(),
+                status: SubmissionStatus::Ok,
+                points: vec![],
+                valgrind: None,
+                submission_url: submission_url.to_string(),
+                solution_url: None,
+                submitted_at: "2021-01-01T00:00:00.000+00:00".to_string(),
+                processing_time: None,
+                reviewed: false,
+                requests_review: false,
+                paste_url: None,
+                message_for_paste: None,
+                missing_review_points: vec![],
+                test_cases: Some(vec![]),
+                feedback_questions: None,
+                feedback_answer_url: None,
+                error: None,
+            });
+        }
+
+        self.tmc_client
+            .wait_for_submission_at(submission_url)
+            .map_err(CliError::from_server_error)
     }
     pub fn update_exercises(
         &mut self,
@@ -282,6 +458,13 @@ impl Client {
     }
 
     pub fn get_course_exercises(&mut self, course_id: u32) -> anyhow::Result<Vec<CourseExercise>> {
+        // the exercises contain the points awarded to the user
+        self.cached_progress(&cache::course_exercises_key(course_id), |client| {
+            client.fetch_course_exercises(course_id)
+        })
+    }
+
+    fn fetch_course_exercises(&self, course_id: u32) -> anyhow::Result<Vec<CourseExercise>> {
         if self.test_mode {
             return Ok(vec![CourseExercise {
                 id: 0,
@@ -298,10 +481,52 @@ impl Client {
         }
         match self.tmc_client.get_course_exercises(course_id) {
             Ok(exercises) => Ok(exercises),
-            Err(TestMyCodeClientError::NotAuthenticated) => {
-                anyhow::bail!("Login token is invalid. Please try logging in again.")
-            }
-            Err(err) => anyhow::bail!("Unexpected error: '{err}'."),
+            Err(err) => Err(CliError::from_client_error(err)),
+        }
+    }
+
+    pub fn get_exercise_submissions(&self, exercise_id: u32) -> anyhow::Result<Vec<Submission>> {
+        if self.test_mode {
+            return Ok(test_mode_submissions());
+        }
+        match self
+            .tmc_client
+            .get_exercise_submissions_for_current_user(exercise_id)
+        {
+            Ok(submissions) => Ok(submissions),
+            Err(err) => Err(CliError::from_client_error(err)),
+        }
+    }
+
+    pub fn get_course_submissions(&self, course_id: u32) -> anyhow::Result<Vec<Submission>> {
+        if self.test_mode {
+            return Ok(test_mode_submissions());
+        }
+        match self
+            .tmc_client
+            .get_course_submissions_for_current_user(course_id)
+        {
+            Ok(submissions) => Ok(submissions),
+            Err(err) => Err(CliError::from_client_error(err)),
+        }
+    }
+
+    /// URL of the page that shows a submission on the server
+    pub fn submission_url(&self, submission_id: u32) -> String {
+        format!(
+            "{}/submissions/{submission_id}",
+            self.root_url.as_str().trim_end_matches('/')
+        )
+    }
+
+    pub fn download_model_solution(&self, exercise_id: u32, target: &Path) -> anyhow::Result<()> {
+        if self.test_mode {
+            std::fs::create_dir_all(target)?;
+            return Ok(());
+        }
+        match self.tmc_client.download_model_solution(exercise_id, target) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(CliError::from_client_error(err)),
         }
     }
 
@@ -325,10 +550,14 @@ impl Client {
         )
     }
 
-    pub fn get_course_details(
-        &self,
-        course_id: u32,
-    ) -> Result<CourseDetails, TestMyCodeClientError> {
+    pub fn get_course_details(&self, course_id: u32) -> anyhow::Result<CourseDetails> {
+        // the exercises contain whether the user has completed them
+        self.cached_progress(&cache::course_details_key(course_id), |client| {
+            client.fetch_course_details(course_id)
+        })
+    }
+
+    fn fetch_course_details(&self, course_id: u32) -> anyhow::Result<CourseDetails> {
         if self.test_mode {
             let course = Course {
                 id: 0,
@@ -347,7 +576,9 @@ impl Client {
                 exercises: vec![],
             })
         } else {
-            self.tmc_client.get_course_details(course_id)
+            self.tmc_client
+                .get_course_details(course_id)
+                .map_err(CliError::from_client_error)
         }
     }
 
@@ -356,3 +587,62 @@ impl Client {
         self.tmc_client.set_token(token);
     }
 }
+
+/// The previous submissions of the user in test mode
+fn test_mode_submissions() -> Vec<Submission> {
+    vec![Submission {
+        id: 7400888,
+        user_id: 0,
+        pretest_error: None,
+        created_at: chrono::DateTime::parse_from_rfc3339("2021-01-01T00:00:00+00:00")
+            .expect("valid timestamp"),
+        exercise_name: "part01-01_hello".to_string(),
+        course_id: 0,
+        processed: true,
+        all_tests_passed: true,
+        points: Some("1.1 1.2".to_string()),
+        paste_key: None,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    #[test]
+    fn fresh_profile_sends_no_token_test() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", Matcher::Any)
+            .match_header("authorization", Matcher::Missing)
+            .with_body("[]")
+            .create();
+        let credentials_name = format!("{PLUGIN}-test-{}", uuid::Uuid::new_v4());
+
+        let client = Client::new(server.url().parse().unwrap(), credentials_name, false).unwrap();
+        let organizations = client.fetch_organizations().unwrap();
+
+        assert!(organizations.is_empty());
+        mock.assert();
+    }
+
+    #[test]
+    fn explain_login_fail_test() {
+        let http_error = |status: u16| {
+            LangsError::TestMyCodeClient(TestMyCodeClientError::HttpError {
+                url: "http://localhost/oauth/token".parse().unwrap(),
+                status: StatusCode::from_u16(status).unwrap(),
+                error: "error".to_string(),
+                obsolete_client: false,
+            })
+        };
+
+        for status in [400, 401] {
+            let err = Client::explain_login_fail(http_error(status));
+            assert_eq!(error::exit_code(&err), 4);
+        }
+        let err = Client::explain_login_fail(http_error(500));
+        assert_eq!(error::exit_code(&err), 9);
+    }
+}
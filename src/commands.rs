@@ -1,4 +1,6 @@
+mod clean;
 mod courses;
+mod deadlines;
 mod download;
 mod exercises;
 mod generate_completions;
@@ -6,28 +8,51 @@ mod login;
 mod logout;
 mod organization;
 mod paste;
+mod profile;
+mod reset;
+mod solution;
+mod status;
+mod submissions;
 mod submit;
-mod test;
+pub mod test;
 mod update;
 pub mod util;
+pub mod whoami;
 
 use crate::{
+    cache::{Cache, CacheMode},
     cli::{Cli, Command},
     client::Client,
     config::TmcCliConfig,
-    io::Io,
+    error::CliError,
+    io::{Io, PrintColor},
 };
 use anyhow::Context;
 use std::env;
 
 pub fn handle(cli: Cli, io: &mut Io, mut config: TmcCliConfig) -> anyhow::Result<()> {
-    let tmc_root_url = match env::var("TMC_LANGS_TMC_ROOT_URL") {
-        Ok(url) => url
-            .parse()
-            .with_context(|| format!("Failed to parse TMC_LANGS_TMC_ROOT_URL ({url}) as a URL"))?,
-        Err(_) => "https://tmc.mooc.fi".parse().expect("known to work"),
+    config.select_profile(cli.profile.as_deref())?;
+    let tmc_root_url = if let Some(url) = config.get_root_url() {
+        url.parse().with_context(|| {
+            format!("Failed to parse the root URL of the profile ({url}) as a URL")
+        })?
+    } else {
+        match env::var("TMC_LANGS_TMC_ROOT_URL") {
+            Ok(url) => url.parse().with_context(|| {
+                format!("Failed to parse TMC_LANGS_TMC_ROOT_URL ({url}) as a URL")
+            })?,
+            Err(_) => "https://tmc.mooc.fi".parse().expect("known to work"),
+        }
+    };
+    let mut client = Client::new(tmc_root_url, config.credentials_name(), cli.testmode)?;
+    let cache_mode = if cli.offline {
+        CacheMode::Offline
+    } else if cli.refresh {
+        CacheMode::Refresh
+    } else {
+        CacheMode::Normal
     };
-    let mut client = Client::new(tmc_root_url, cli.testmode)?;
+    client.set_cache(Cache::new(config.cache_dir()?, cache_mode));
 
     let require_logged_out = |client: &mut Client| {
         let exists = client.load_login(&config).is_ok();
@@ -39,7 +64,7 @@ pub fn handle(cli: Cli, io: &mut Io, mut config: TmcCliConfig) -> anyhow::Result
     let require_logged_in = |client: &mut Client| {
         let exists = client.load_login(&config).is_ok();
         if !exists {
-            anyhow::bail!("No login found. Login to use this command with 'tmc login'");
+            return Err(CliError::NotLoggedIn.into());
         }
         anyhow::Ok(())
     };
@@ -49,19 +74,63 @@ pub fn handle(cli: Cli, io: &mut Io, mut config: TmcCliConfig) -> anyhow::Result
 
     match cli.subcommand {
         // tmc commands
-        Command::Login { non_interactive } => {
+        Command::Login {
+            non_interactive,
+            username,
+            password_stdin,
+            token,
+            token_stdin,
+            organization,
+        } => {
             require_logged_out(&mut client)?;
             let interactive_mode = !non_interactive;
-            login::login(io, &mut client, interactive_mode, &mut config)?;
+            let method = if token.is_some() || token_stdin {
+                login::LoginMethod::Token { token }
+            } else {
+                login::LoginMethod::Password {
+                    username,
+                    password_stdin,
+                }
+            };
+            login::login(
+                io,
+                &mut client,
+                interactive_mode,
+                &mut config,
+                method,
+                organization.as_deref(),
+            )?;
+        }
+        Command::Deadlines { ics } => {
+            require_logged_in(&mut client)?;
+            let org = require_org()?;
+            deadlines::deadlines(io, &mut client, &config, org, ics.as_deref())?;
         }
-        Command::Download { course, currentdir } => {
+        Command::Download {
+            course,
+            currentdir,
+            exercise,
+            part,
+            due_before,
+            select,
+        } => {
             require_logged_in(&mut client)?;
             let org = require_org()?;
+            let filter = download::ExerciseFilter {
+                pattern: exercise,
+                part,
+                due_before: due_before
+                    .as_deref()
+                    .map(download::ExerciseFilter::parse_date)
+                    .transpose()?,
+            };
             download::download_or_update(
                 io,
                 &mut client,
                 course.as_deref(),
                 currentdir,
+                &filter,
+                select,
                 &config,
                 org,
             )?;
@@ -70,32 +139,91 @@ pub fn handle(cli: Cli, io: &mut Io, mut config: TmcCliConfig) -> anyhow::Result
             require_logged_in(&mut client)?;
             update::update(io, &mut client, currentdir, &config)?;
         }
+        Command::Whoami => {
+            whoami::whoami(io, &mut client, &config)?;
+        }
         Command::Organization { non_interactive } => {
             require_logged_in(&mut client)?;
             let interactive_mode = !non_interactive;
             organization::organization(io, &mut client, interactive_mode, &mut config)?;
         }
+        Command::Clean { exercise, course } => {
+            clean::clean(io, exercise.as_deref(), course.as_deref(), &config)?;
+        }
         Command::Courses => {
             require_logged_in(&mut client)?;
             let org = require_org()?;
             courses::list_courses(io, &mut client, org)?;
         }
-        Command::Submit { exercise } => {
+        Command::Submissions { exercise, course } => {
+            require_logged_in(&mut client)?;
+            submissions::submissions(
+                io,
+                &mut client,
+                exercise.as_deref(),
+                course.as_deref(),
+                &config,
+                config.get_organization(),
+            )?;
+        }
+        Command::Submit {
+            exercise,
+            passing,
+            course,
+        } => {
             require_logged_in(&mut client)?;
-            submit::submit(io, &mut client, exercise.as_deref(), &config)?;
+            if passing {
+                submit::submit_passing(io, &mut client, course.as_deref(), &config)?;
+            } else {
+                submit::submit(io, &mut client, exercise.as_deref(), &config)?;
+            }
         }
         Command::Exercises { course } => {
             require_logged_in(&mut client)?;
             let org = require_org()?;
             exercises::list_exercises(io, &mut client, course.as_deref(), org)?
         }
-        Command::Test { exercise } => {
-            test::test(io, exercise.as_deref(), &config)?;
+        Command::Test {
+            exercise,
+            all,
+            course,
+            watch,
+            report,
+        } => {
+            if io.is_json() && report.iter().any(|report| report.path.is_none()) {
+                anyhow::bail!(
+                    "A report can't be written to stdout together with the JSON output. Give a file for the report, for example --report junit=results.xml"
+                );
+            }
+            if all {
+                test::test_all(io, course.as_deref(), &config, &report)?;
+            } else if watch {
+                test::watch(io, exercise.as_deref(), &config)?;
+            } else {
+                test::test(io, exercise.as_deref(), &config, &report)?;
+            }
         }
         Command::Paste { exercise } => {
             require_logged_in(&mut client)?;
             paste::paste(io, &mut client, exercise.as_deref(), &config)?;
         }
+        Command::Profile { command } => {
+            profile::profile(io, command, &mut config)?;
+        }
+        Command::Reset { exercise, yes } => {
+            require_logged_in(&mut client)?;
+            reset::reset(io, &mut client, exercise.as_deref(), yes, &config)?;
+        }
+        Command::Solution { exercise } => {
+            require_logged_in(&mut client)?;
+            let org = require_org()?;
+            solution::solution(io, &mut client, exercise.as_deref(), &config, org)?;
+        }
+        Command::Status { course } => {
+            require_logged_in(&mut client)?;
+            let org = require_org()?;
+            status::status(io, &mut client, course.as_deref(), &config, org)?;
+        }
         Command::Logout => {
             require_logged_in(&mut client)?;
             logout::logout(io, &mut client, &mut config)?;
@@ -121,5 +249,17 @@ pub fn handle(cli: Cli, io: &mut Io, mut config: TmcCliConfig) -> anyhow::Result
             generate_completions::generate(shell);
         }
     }
+
+    if let Some(fetched_at) = client.offline_data_fetched_at() {
+        io.println(
+            &format!(
+                "Offline: showing cached data from {}",
+                fetched_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+            ),
+            PrintColor::Warning,
+        )?;
+    }
     Ok(())
 }
@@ -0,0 +1,187 @@
+use super::util;
+use crate::{
+    config::TmcCliConfig,
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Removes build artifacts from an exercise or from every downloaded exercise of a course.
+/// Path to the exercise can be given as a parameter or
+/// the user can run the command in the exercise folder.
+///
+/// # Errors
+/// Returns an error if no exercise or course was found or cleaning an exercise failed.
+pub fn clean(
+    io: &mut Io,
+    exercise: Option<&str>,
+    course: Option<&str>,
+    config: &TmcCliConfig,
+) -> anyhow::Result<()> {
+    let exercise_dirs = if let Some(course) = course {
+        util::course_exercise_dirs(Some(course), config)?.1
+    } else {
+        vec![util::exercise_pathfinder(io, exercise, config).context("Error finding exercise")?]
+    };
+    clean_exercises(io, &exercise_dirs)
+}
+
+/// Cleans the exercises and prints how much space was reclaimed.
+/// Exercises whose directory has been removed are skipped with a warning.
+fn clean_exercises(io: &mut Io, exercise_dirs: &[PathBuf]) -> anyhow::Result<()> {
+    let mut cleaned = vec![];
+    let mut skipped = vec![];
+    let mut failed = vec![];
+    for exercise_dir in exercise_dirs {
+        let name = util::exercise_name(exercise_dir);
+        if !exercise_dir.is_dir() {
+            io.println(
+                &format!(
+                    "Skipping {name}, the directory {} does not exist",
+                    exercise_dir.display()
+                ),
+                PrintColor::Warning,
+            )?;
+            skipped.push(serde_json::json!({
+                "exercise": name,
+                "path": exercise_dir,
+            }));
+            continue;
+        }
+        match clean_exercise(exercise_dir) {
+            Ok(reclaimed) => {
+                io.println(
+                    &format!("Cleaned {name}, reclaimed {}", format_size(reclaimed)),
+                    PrintColor::Normal,
+                )?;
+                cleaned.push(serde_json::json!({
+                    "exercise": name,
+                    "path": exercise_dir,
+                    "reclaimed": reclaimed,
+                }));
+            }
+            Err(err) => {
+                io.println(
+                    &format!("Failed to clean {name}: {err:#}"),
+                    PrintColor::Failed,
+                )?;
+                failed.push(serde_json::json!({
+                    "exercise": name,
+                    "error": format!("{err:#}"),
+                }));
+            }
+        }
+    }
+
+    let total = cleaned
+        .iter()
+        .filter_map(|exercise| exercise["reclaimed"].as_u64())
+        .sum::<u64>();
+    io.print_json(&serde_json::json!({
+        "cleaned": cleaned,
+        "skipped": skipped,
+        "failed": failed,
+        "reclaimed": total,
+    }))?;
+    if exercise_dirs.len() > 1 {
+        io.println(
+            &format!("\nReclaimed {} in total", format_size(total)),
+            PrintColor::Success,
+        )?;
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!("Failed to clean {} exercise(s)", failed.len());
+    }
+    Ok(())
+}
+
+/// Cleans an exercise and returns the number of bytes that were freed
+fn clean_exercise(exercise_dir: &Path) -> anyhow::Result<u64> {
+    let before = dir_size(exercise_dir)?;
+    tmc_langs::clean(exercise_dir)
+        .with_context(|| format!("Failed to clean {}", exercise_dir.display()))?;
+    let after = dir_size(exercise_dir)?;
+    Ok(before.saturating_sub(after))
+}
+
+/// Total size of the files in a directory. Symlinks are not followed.
+fn dir_size(path: &Path) -> anyhow::Result<u64> {
+    let mut size = 0;
+    let mut dirs: Vec<PathBuf> = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries =
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.path().symlink_metadata()?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                size += metadata.len();
+            }
+        }
+    }
+    Ok(size)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper;
+
+    #[test]
+    fn dir_size_test() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("a"), [0; 100]).unwrap();
+        fs::create_dir_all(temp.path().join("target/classes")).unwrap();
+        fs::write(temp.path().join("target/classes/b"), [0; 50]).unwrap();
+
+        assert_eq!(dir_size(temp.path()).unwrap(), 150);
+    }
+
+    #[test]
+    fn missing_exercise_is_skipped_test() {
+        let temp = tempfile::tempdir().unwrap();
+        let missing = temp.path().join("test-course").join("part01-01_removed");
+
+        let (mut input, mut output) = test_helper::input_output();
+        {
+            let mut io = Io::new(&mut output, &mut input);
+            clean_exercises(&mut io, &[missing]).unwrap();
+        }
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        assert!(output.contains("Skipping part01-01_removed"), "{output}");
+        assert!(!output.contains("Failed"), "{output}");
+    }
+
+    #[test]
+    fn format_size_test() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
 pub fn list_courses(io: &mut Io, client: &mut Client, org: &str) -> anyhow::Result<()> {
     let mut course_list = client.list_courses(org)?;
     course_list.sort_unstable_by(|l, r| l.name.cmp(&r.name));
+    io.print_json(&course_list)?;
     io.println("", PrintColor::Normal)?;
     for course in course_list {
         io.println(&course.name, PrintColor::Normal)?;
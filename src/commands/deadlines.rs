@@ -0,0 +1,341 @@
+mod calendar;
+
+use super::{
+    exercises::{self, Completion},
+    util,
+};
+use crate::{
+    client::Client,
+    config::TmcCliConfig,
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+use std::path::Path;
+use tmc_langs::{tmc::response::CourseExercise, ProjectsConfig};
+
+/// An unfinished exercise with a deadline
+struct AgendaItem<'a> {
+    course: &'a str,
+    exercise: &'a CourseExercise,
+    due: DateTime<FixedOffset>,
+    state: DeadlineState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeadlineState {
+    /// The next deadline is the soft deadline
+    SoftDeadline,
+    /// The next deadline is the deadline
+    Deadline,
+    /// The soft deadline has passed, the deadline has not
+    SoftDeadlinePassed,
+    Overdue,
+}
+
+impl DeadlineState {
+    fn label(self) -> &'static str {
+        match self {
+            Self::SoftDeadline => "soft deadline",
+            Self::Deadline => "deadline",
+            Self::SoftDeadlinePassed => "soft deadline passed",
+            Self::Overdue => "overdue",
+        }
+    }
+}
+
+/// Shows the deadlines of the unfinished exercises of every downloaded course in chronological order.
+/// If ics_path is given, every deadline is exported to an iCalendar file instead.
+///
+/// # Errors
+/// Returns an error if no downloaded courses were found or the server could not be reached.
+pub fn deadlines(
+    io: &mut Io,
+    client: &mut Client,
+    config: &TmcCliConfig,
+    org: &str,
+    ics_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let courses = downloaded_course_exercises(io, client, config, org)?;
+    let now = Utc::now();
+    if let Some(ics_path) = ics_path {
+        return export_calendar(io, &courses, now, ics_path);
+    }
+    let items = agenda(&courses, now);
+
+    io.print_json(
+        &items
+            .iter()
+            .map(|item| {
+                serde_json::json!({
+                    "course": item.course,
+                    "exercise": item.exercise.name,
+                    "id": item.exercise.id,
+                    "due": item.due.to_rfc3339(),
+                    "state": item.state.label(),
+                    "deadline": item.exercise.deadline,
+                    "soft_deadline": item.exercise.soft_deadline,
+                })
+            })
+            .collect::<Vec<_>>(),
+    )?;
+    print_agenda(io, &items, now, &Local)
+}
+
+fn export_calendar(
+    io: &mut Io,
+    courses: &[(String, Vec<CourseExercise>)],
+    now: DateTime<Utc>,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let events = calendar::events(courses);
+    std::fs::write(path, calendar::calendar(&events, now))
+        .with_context(|| format!("Failed to write calendar to {}", path.display()))?;
+
+    io.print_json(&serde_json::json!({
+        "path": path,
+        "events": events.len(),
+    }))?;
+    io.println(
+        &format!("Exported {} deadlines to {}", events.len(), path.display()),
+        PrintColor::Success,
+    )?;
+    Ok(())
+}
+
+/// Fetches the exercises of every downloaded course from the server
+fn downloaded_course_exercises(
+    io: &mut Io,
+    client: &mut Client,
+    config: &TmcCliConfig,
+    org: &str,
+) -> anyhow::Result<Vec<(String, Vec<CourseExercise>)>> {
+    let projects_dir = config.get_projects_dir();
+    let projects_config = ProjectsConfig::load(projects_dir)
+        .context("Could not load info about the downloaded courses")?;
+    if projects_config.courses.is_empty() {
+        anyhow::bail!(
+            "No downloaded courses found. Project directory set to {}",
+            projects_dir.display()
+        );
+    }
+
+    io.println("Fetching courses...", PrintColor::Normal)?;
+    let courses = client.list_courses(org)?;
+    let mut course_exercises = Vec::new();
+    for course_slug in projects_config.courses.keys() {
+        let Some(course) = courses.iter().find(|course| &course.name == course_slug) else {
+            io.println(
+                &format!("Course {course_slug} was not found in the selected organization"),
+                PrintColor::Failed,
+            )?;
+            continue;
+        };
+        let exercises = client.get_course_exercises(course.id)?;
+        course_exercises.push((course_slug.clone(), exercises));
+    }
+    Ok(course_exercises)
+}
+
+/// Collects the unfinished exercises that have a deadline, sorted by the next deadline
+fn agenda(courses: &[(String, Vec<CourseExercise>)], now: DateTime<Utc>) -> Vec<AgendaItem<'_>> {
+    let mut items = courses
+        .iter()
+        .flat_map(|(course, exercises)| {
+            exercises
+                .iter()
+                .filter(|exercise| {
+                    !exercise.disabled
+                        && !matches!(exercises::completion(exercise), Completion::Completed)
+                })
+                .filter_map(move |exercise| {
+                    let deadline = exercise.deadline.as_deref().and_then(util::parse_deadline);
+                    let soft_deadline = exercise
+                        .soft_deadline
+                        .as_deref()
+                        .and_then(util::parse_deadline);
+                    let (due, state) = match (soft_deadline, deadline) {
+                        (_, Some(deadline)) if deadline <= now => {
+                            (deadline, DeadlineState::Overdue)
+                        }
+                        (Some(soft_deadline), Some(deadline)) if soft_deadline <= now => {
+                            (deadline, DeadlineState::SoftDeadlinePassed)
+                        }
+                        (Some(soft_deadline), None) if soft_deadline <= now => {
+                            (soft_deadline, DeadlineState::SoftDeadlinePassed)
+                        }
+                        (Some(soft_deadline), _) => (soft_deadline, DeadlineState::SoftDeadline),
+                        (None, Some(deadline)) => (deadline, DeadlineState::Deadline),
+                        (None, None) => return None,
+                    };
+                    Some(AgendaItem {
+                        course: course.as_str(),
+                        exercise,
+                        due,
+                        state,
+                    })
+                })
+        })
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| item.due);
+    items
+}
+
+fn print_agenda<Tz: TimeZone>(
+    io: &mut Io,
+    items: &[AgendaItem],
+    now: DateTime<Utc>,
+    timezone: &Tz,
+) -> anyhow::Result<()>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if items.is_empty() {
+        io.println("No upcoming deadlines", PrintColor::Normal)?;
+        return Ok(());
+    }
+
+    let name_width = items
+        .iter()
+        .map(|item| item.course.chars().count() + item.exercise.name.chars().count() + 1)
+        .max()
+        .unwrap_or_default();
+    for item in items {
+        let color = match item.state {
+            DeadlineState::Overdue => PrintColor::Failed,
+            DeadlineState::SoftDeadlinePassed => PrintColor::Warning,
+            DeadlineState::SoftDeadline | DeadlineState::Deadline => PrintColor::Normal,
+        };
+        io.println(
+            &format!(
+                "  {}  {:<14}  {:name_width$}  ({})",
+                item.due.with_timezone(timezone).format("%a %Y-%m-%d %H:%M"),
+                relative_time(item.due, now),
+                format!("{}/{}", item.course, item.exercise.name),
+                item.state.label(),
+            ),
+            color,
+        )?;
+    }
+    Ok(())
+}
+
+/// Describes how far the given time is from now, for example "in 2 days" or "3 hours ago"
+fn relative_time(time: DateTime<FixedOffset>, now: DateTime<Utc>) -> String {
+    let seconds = time.signed_duration_since(now).num_seconds();
+    let (amount, unit) = match seconds.unsigned_abs() {
+        s if s >= 24 * 60 * 60 => (s / (24 * 60 * 60), "day"),
+        s if s >= 60 * 60 => (s / (60 * 60), "hour"),
+        s if s >= 60 => (s / 60, "minute"),
+        _ => return "now".to_string(),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    if seconds > 0 {
+        format!("in {amount} {unit}{plural}")
+    } else {
+        format!("{amount} {unit}{plural} ago")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(
+        name: &str,
+        deadline: Option<&str>,
+        soft_deadline: Option<&str>,
+        completed: bool,
+    ) -> CourseExercise {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "available_points": [
+                {
+                    "id": 1,
+                    "exercise_id": 1,
+                    "name": "1.1",
+                    "requires_review": false,
+                },
+            ],
+            "awarded_points": if completed { vec!["1.1"] } else { vec![] },
+            "name": name,
+            "publish_time": null,
+            "solution_visible_after": null,
+            "deadline": deadline,
+            "soft_deadline": soft_deadline,
+            "disabled": false,
+            "unlocked": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn relative_time_test() {
+        let now = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+        let time = |s| DateTime::parse_from_rfc3339(s).unwrap();
+
+        assert_eq!(
+            relative_time(time("2021-06-03T12:00:00Z"), now),
+            "in 2 days"
+        );
+        assert_eq!(
+            relative_time(time("2021-06-01T13:30:00Z"), now),
+            "in 1 hour"
+        );
+        assert_eq!(
+            relative_time(time("2021-06-01T11:55:00Z"), now),
+            "5 minutes ago"
+        );
+        assert_eq!(relative_time(time("2021-06-01T12:00:30Z"), now), "now");
+    }
+
+    #[test]
+    fn agenda_test() {
+        let (mut input, mut output) = crate::test_helper::input_output();
+        let mut io = Io::new(&mut output, &mut input);
+
+        let now = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+        let courses = vec![
+            (
+                "course-a".to_string(),
+                vec![
+                    exercise("done", Some("2021-06-02T12:00:00Z"), None, true),
+                    exercise("no-deadline", None, None, false),
+                    exercise("later", Some("2021-06-10T12:00:00Z"), None, false),
+                ],
+            ),
+            (
+                "course-b".to_string(),
+                vec![
+                    exercise("late", Some("2021-05-01T12:00:00Z"), None, false),
+                    exercise(
+                        "soft",
+                        Some("2021-06-03T12:00:00Z"),
+                        Some("2021-05-31T12:00:00Z"),
+                        false,
+                    ),
+                    exercise(
+                        "soon",
+                        Some("2021-06-08T12:00:00Z"),
+                        Some("2021-06-01T18:00:00Z"),
+                        false,
+                    ),
+                ],
+            ),
+        ];
+        let items = agenda(&courses, now);
+        print_agenda(&mut io, &items, now, &Utc).unwrap();
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        let output = output.lines().collect::<Vec<_>>();
+        assert_eq!(
+            output,
+            [
+                "  Sat 2021-05-01 12:00  31 days ago     course-b/late   (overdue)",
+                "  Tue 2021-06-01 18:00  in 6 hours      course-b/soon   (soft deadline)",
+                "  Thu 2021-06-03 12:00  in 2 days       course-b/soft   (soft deadline passed)",
+                "  Thu 2021-06-10 12:00  in 9 days       course-a/later  (deadline)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Serializes course deadlines into an iCalendar (RFC 5545) document
+
+use crate::commands::util;
+use chrono::{DateTime, Utc};
+use tmc_langs::tmc::response::CourseExercise;
+
+const PRODUCT_ID: &str = "-//rage//tmc-cli-rust//EN";
+const UID_DOMAIN: &str = "tmc-cli-rust";
+
+/// A single deadline of an exercise
+pub struct CalendarEvent<'a> {
+    course: &'a str,
+    exercise: &'a CourseExercise,
+    time: DateTime<Utc>,
+    soft: bool,
+}
+
+/// Collects an event for the deadline and soft deadline of every exercise of the courses
+pub fn events(courses: &[(String, Vec<CourseExercise>)]) -> Vec<CalendarEvent<'_>> {
+    let mut events = Vec::new();
+    for (course, exercises) in courses {
+        for exercise in exercises.iter().filter(|exercise| !exercise.disabled) {
+            let deadlines = [(&exercise.deadline, false), (&exercise.soft_deadline, true)];
+            for (deadline, soft) in deadlines {
+                if let Some(time) = deadline.as_deref().and_then(util::parse_deadline) {
+                    events.push(CalendarEvent {
+                        course,
+                        exercise,
+                        time: time.with_timezone(&Utc),
+                        soft,
+                    });
+                }
+            }
+        }
+    }
+    events.sort_by_key(|event| event.time);
+    events
+}
+
+/// Serializes the events into a calendar.
+/// The UIDs only depend on the exercise id so importing the calendar again updates the events.
+pub fn calendar(events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    for event in events {
+        let (kind, uid_kind) = if event.soft {
+            ("Soft deadline", "soft-deadline")
+        } else {
+            ("Deadline", "deadline")
+        };
+        let time = format_time(event.time);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:exercise-{}-{uid_kind}@{UID_DOMAIN}",
+            event.exercise.id
+        ));
+        lines.push(format!("DTSTAMP:{}", format_time(now)));
+        lines.push(format!("DTSTART:{time}"));
+        lines.push(format!("DTEND:{time}"));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(&format!("{kind}: {}", event.exercise.name))
+        ));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&format!(
+                "{kind} of exercise {} in course {}",
+                event.exercise.name, event.course
+            ))
+        ));
+        lines.push(format!("CATEGORIES:{}", escape_text(event.course)));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold_line(&line));
+        calendar.push_str("\r\n");
+    }
+    calendar
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits lines longer than 75 octets, continuation lines start with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_length = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn exercise(
+        id: u32,
+        name: &str,
+        deadline: &str,
+        soft_deadline: Option<&str>,
+    ) -> CourseExercise {
+        CourseExercise {
+            id,
+            available_points: vec![],
+            awarded_points: vec![],
+            name: name.to_string(),
+            publish_time: None,
+            solution_visible_after: None,
+            deadline: Some(deadline.to_string()),
+            soft_deadline: soft_deadline.map(str::to_string),
+            disabled: false,
+            unlocked: true,
+        }
+    }
+
+    #[test]
+    fn calendar_test() {
+        let courses = vec![(
+            "test-course".to_string(),
+            vec![
+                exercise(2, "part01-02", "2021-06-10T23:59:00+03:00", None),
+                exercise(
+                    1,
+                    "part01-01",
+                    "2021-06-03T23:59:00+03:00",
+                    Some("2021-06-01T23:59:00+03:00"),
+                ),
+            ],
+        )];
+        let now = Utc.with_ymd_and_hms(2021, 5, 1, 12, 0, 0).unwrap();
+        let calendar = calendar(&events(&courses), now);
+
+        let expected = [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//rage//tmc-cli-rust//EN",
+            "CALSCALE:GREGORIAN",
+            "METHOD:PUBLISH",
+            "BEGIN:VEVENT",
+            "UID:exercise-1-soft-deadline@tmc-cli-rust",
+            "DTSTAMP:20210501T120000Z",
+            "DTSTART:20210601T205900Z",
+            "DTEND:20210601T205900Z",
+            "SUMMARY:Soft deadline: part01-01",
+            "DESCRIPTION:Soft deadline of exercise part01-01 in course test-course",
+            "CATEGORIES:test-course",
+            "TRANSP:TRANSPARENT",
+            "END:VEVENT",
+            "BEGIN:VEVENT",
+            "UID:exercise-1-deadline@tmc-cli-rust",
+            "DTSTAMP:20210501T120000Z",
+            "DTSTART:20210603T205900Z",
+            "DTEND:20210603T205900Z",
+            "SUMMARY:Deadline: part01-01",
+            "DESCRIPTION:Deadline of exercise part01-01 in course test-course",
+            "CATEGORIES:test-course",
+            "TRANSP:TRANSPARENT",
+            "END:VEVENT",
+            "BEGIN:VEVENT",
+            "UID:exercise-2-deadline@tmc-cli-rust",
+            "DTSTAMP:20210501T120000Z",
+            "DTSTART:20210610T205900Z",
+            "DTEND:20210610T205900Z",
+            "SUMMARY:Deadline: part01-02",
+            "DESCRIPTION:Deadline of exercise part01-02 in course test-course",
+            "CATEGORIES:test-course",
+            "TRANSP:TRANSPARENT",
+            "END:VEVENT",
+            "END:VCALENDAR",
+            "",
+        ];
+        assert_eq!(calendar, expected.join("\r\n"));
+    }
+
+    #[test]
+    fn escape_and_fold_test() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = fold_line(&line);
+        let parts = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
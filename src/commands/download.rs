@@ -1,27 +1,38 @@
-use super::util;
+use super::{exercises, util};
 use crate::{
     client::Client,
     config::TmcCliConfig,
+    error::CliError,
+    interactive,
     io::{Io, PrintColor},
     progress_reporting,
     progress_reporting::ProgressBarManager,
 };
 use anyhow::Context;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
 use std::{path::Path, process::Command};
 use tmc_langs::{
-    tmc::{response::Course, ClientUpdateData},
-    DownloadResult,
+    tmc::{
+        response::{Course, CourseExercise},
+        ClientUpdateData,
+    },
+    DownloadResult, ExerciseDownload,
 };
 
 // Downloads course exercises
 // course_name as None will trigger interactive menu for selecting a course
 // currentdir determines if course should be downloaded to current directory or central project directory
+// filter narrows down the downloaded exercises
+// select opens an interactive menu for choosing which exercises are downloaded
 // Will run in privileged stage if needed on Windows.
+#[allow(clippy::too_many_arguments)]
 pub fn download_or_update(
     io: &mut Io,
     client: &mut Client,
     course_name: Option<&str>,
     current_dir: bool,
+    filter: &ExerciseFilter,
+    select: bool,
     config: &TmcCliConfig,
     org: &str,
 ) -> anyhow::Result<()> {
@@ -34,17 +45,40 @@ pub fn download_or_update(
     };
 
     // Get course by name
-    let course = match util::get_course_by_name(client, name_select, org)? {
-        Some(course) => course,
-        None => anyhow::bail!("Could not find course with that name"),
-    };
+    let course = util::find_course(client, name_select, org)?;
     let path = if current_dir {
         std::env::current_dir()?
     } else {
         config.get_projects_dir().to_path_buf()
     };
 
-    match download_exercises(&path, client, &course) {
+    let exercises = downloadable_exercises(client, &course)?;
+    let exercises = filter.apply(exercises);
+    if exercises.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "No exercises of course '{}' match the given filters",
+            course.name
+        ))
+        .into());
+    }
+    let exercise_ids = if select {
+        select_exercises(io, &exercises)?
+    } else {
+        exercises.iter().map(|exercise| exercise.id).collect()
+    };
+    if select || !filter.is_empty() {
+        let selected = exercises
+            .iter()
+            .filter(|exercise| exercise_ids.contains(&exercise.id))
+            .map(|exercise| format!("\n  {}", exercise.name))
+            .collect::<String>();
+        io.println(
+            &format!("Selected {} exercises:{selected}", exercise_ids.len()),
+            PrintColor::Normal,
+        )?;
+    }
+
+    match download_exercise_ids(io, &path, client, &exercise_ids) {
         Ok(msg) => {
             io.println(&format!("\n{msg}"), PrintColor::Success)?;
             Ok(())
@@ -62,9 +96,14 @@ pub fn download_or_update(
                 )?;
                 let temp_file_path = config.get_projects_dir();
                 let temp_file_path = temp_file_path.join("temp.txt");
+                let exercise_ids = exercise_ids
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
                 std::fs::write(
                     temp_file_path,
-                    format!("{};{}", &path.display(), &course.name),
+                    format!("{};{};{}", &path.display(), &course.name, exercise_ids),
                 )?;
                 Command::new("cmd")
                     .args([
@@ -88,82 +127,249 @@ pub fn download_or_update(
 }
 
 pub fn download_exercises(
+    io: &mut Io,
     projects_dir: &Path,
     client: &mut Client,
     course: &Course,
 ) -> anyhow::Result<String> {
-    match client.get_course_exercises(course.id) {
-        Ok(exercises) => {
-            let exercise_ids: Vec<u32> = exercises
-                .iter()
-                .filter(|t| !t.disabled && t.unlocked)
-                .map(|t| t.id)
-                .collect();
-
-            if exercise_ids.is_empty() {
-                anyhow::bail!(format!(
-                    "No valid exercises found for course '{}'",
-                    course.title
-                ));
+    let exercise_ids = downloadable_exercises(client, course)?
+        .iter()
+        .map(|exercise| exercise.id)
+        .collect::<Vec<_>>();
+    download_exercise_ids(io, projects_dir, client, &exercise_ids)
+}
+
+/// Narrows down the exercises to download, an empty filter matches every exercise
+#[derive(Debug, Default)]
+pub struct ExerciseFilter {
+    /// Glob pattern matched against the exercise name, `*` matches any text and `?` any character
+    pub pattern: Option<String>,
+    /// Part of the course, from the `partNN-` prefix of the exercise names
+    pub part: Option<u32>,
+    /// Only exercises with a deadline before this
+    pub due_before: Option<DateTime<FixedOffset>>,
+}
+
+impl ExerciseFilter {
+    /// Parses the date of --due-before, either an RFC 3339 time or a date that means midnight local time
+    pub fn parse_date(date: &str) -> anyhow::Result<DateTime<FixedOffset>> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(date) {
+            return Ok(time);
+        }
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").with_context(|| {
+            format!("Invalid date '{date}', expected a date such as 2024-01-31")
+        })?;
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .map(|midnight| midnight.fixed_offset())
+            .with_context(|| format!("Invalid date '{date}'"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_none() && self.part.is_none() && self.due_before.is_none()
+    }
+
+    pub fn apply(&self, exercises: Vec<CourseExercise>) -> Vec<CourseExercise> {
+        exercises
+            .into_iter()
+            .filter(|exercise| self.matches(exercise))
+            .collect()
+    }
+
+    fn matches(&self, exercise: &CourseExercise) -> bool {
+        let pattern_matches = self
+            .pattern
+            .as_deref()
+            .map_or(true, |pattern| glob_match(pattern, &exercise.name));
+        let part_matches = self
+            .part
+            .map_or(true, |part| exercise_part(&exercise.name) == Some(part));
+        let due_matches = self.due_before.map_or(true, |due_before| {
+            exercise
+                .deadline
+                .as_deref()
+                .and_then(util::parse_deadline)
+                .is_some_and(|deadline| deadline < due_before)
+        });
+        pattern_matches && part_matches && due_matches
+    }
+}
+
+/// The part number in exercise names such as "part01-02_hello"
+fn exercise_part(name: &str) -> Option<u32> {
+    let rest = name.strip_prefix("part")?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(rest, |end| &rest[..end]);
+    digits.parse().ok()
+}
+
+/// Matches the whole text against a pattern where `*` matches any text and `?` any single character
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // position of the last * in the pattern and the text position it was tried at
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
             }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // let the last * match one more character
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
 
-            // start manager for 1 event: tmc_langs::download_or_update_exercises
-            let mut manager = ProgressBarManager::new(
-                progress_reporting::get_default_style(),
-                1,
-                client.is_test_mode(),
-            );
-            manager.start::<ClientUpdateData>();
-
-            let result = client.download_or_update_exercises(&exercise_ids, projects_dir);
-
-            match result {
-                Ok(download_result) => {
-                    manager.join();
-                    match download_result {
-                        DownloadResult::Success {
-                            downloaded: _,
-                            skipped: _,
-                        } => {
-                            if client.is_test_mode() {
-                                return Ok("Download was successful!".to_string());
-                            }
-                        }
-                        DownloadResult::Failure {
-                            downloaded,
-                            skipped: _,
-                            failed,
-                        } => {
-                            let mut res = String::from("");
-
-                            for (id, messages) in failed {
-                                res.push_str(&format!(
-                                    "\nFailed to download exercise: '{}'",
-                                    id.exercise_slug
-                                ));
-                                for message in messages {
-                                    res.push_str(&format!("\n    with message: '{message}'"));
-                                }
-                            }
-
-                            if !downloaded.is_empty() {
-                                res.push_str(&format!(
-                                    "\n\nSuccessful downloads saved to {}",
-                                    projects_dir.display()
-                                ));
-                            }
-
-                            anyhow::bail!(res);
-                        }
+/// The unlocked and enabled exercises of the course
+fn downloadable_exercises(
+    client: &mut Client,
+    course: &Course,
+) -> anyhow::Result<Vec<CourseExercise>> {
+    let exercises = client
+        .get_course_exercises(course.id)?
+        .into_iter()
+        .filter(|t| !t.disabled && t.unlocked)
+        .collect::<Vec<_>>();
+
+    if exercises.is_empty() {
+        anyhow::bail!(format!(
+            "No valid exercises found for course '{}'",
+            course.title
+        ));
+    }
+    Ok(exercises)
+}
+
+/// Lets the user choose the exercises to download from an interactive menu
+fn select_exercises(io: &mut Io, exercises: &[CourseExercise]) -> anyhow::Result<Vec<u32>> {
+    let labels = exercise_labels(exercises);
+    let labels = labels.iter().map(String::as_str).collect::<Vec<_>>();
+    let details = |index: usize| exercises::describe_exercise(&exercises[index]);
+    let selected = interactive::interactive_multi_select(
+        io,
+        "Select exercises to download:",
+        &labels,
+        Some(&details),
+    )?
+    .ok_or_else(|| CliError::Cancelled("Didn't select any exercises".to_string()))?;
+    Ok(selected
+        .into_iter()
+        .map(|index| exercises[index].id)
+        .collect())
+}
+
+/// The names of the exercises with their deadlines, aligned into columns
+fn exercise_labels(exercises: &[CourseExercise]) -> Vec<String> {
+    let width = exercises
+        .iter()
+        .map(|exercise| exercise.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    exercises
+        .iter()
+        .map(|exercise| match &exercise.deadline {
+            Some(deadline) => format!(
+                "{:<width$}  deadline {}",
+                exercise.name,
+                util::format_deadline(deadline)
+            ),
+            None => exercise.name.clone(),
+        })
+        .collect()
+}
+
+fn download_exercise_ids(
+    io: &mut Io,
+    projects_dir: &Path,
+    client: &mut Client,
+    exercise_ids: &[u32],
+) -> anyhow::Result<String> {
+    // start manager for 1 event: tmc_langs::download_or_update_exercises
+    let mut manager = ProgressBarManager::new(
+        progress_reporting::get_default_style(),
+        1,
+        client.is_test_mode(),
+    );
+    manager.start::<ClientUpdateData>();
+
+    let result = client.download_or_update_exercises(exercise_ids, projects_dir);
+
+    match result {
+        Ok(download_result) => {
+            manager.join();
+            match download_result {
+                DownloadResult::Success {
+                    downloaded,
+                    skipped,
+                } => {
+                    io.print_json(&download_summary(&downloaded, &skipped, &[]))?;
+                    if !downloaded.is_empty() {
+                        io.println(
+                            &format!("Downloaded: {}", slugs(&downloaded)),
+                            PrintColor::Normal,
+                        )?;
+                    }
+                    if !skipped.is_empty() {
+                        io.println(&format!("Skipped: {}", slugs(&skipped)), PrintColor::Normal)?;
+                    }
+                    if client.is_test_mode() {
+                        return Ok("Download was successful!".to_string());
                     }
                 }
-                Err(err) => {
-                    manager.force_join();
-                    anyhow::bail!(err);
+                DownloadResult::Failure {
+                    downloaded,
+                    skipped,
+                    failed,
+                } => {
+                    io.print_json(&download_summary(&downloaded, &skipped, &failed))?;
+                    let mut res = String::from("");
+                    if !downloaded.is_empty() {
+                        res.push_str(&format!("Downloaded: {}\n", slugs(&downloaded)));
+                    }
+                    if !skipped.is_empty() {
+                        res.push_str(&format!("Skipped: {}\n", slugs(&skipped)));
+                    }
+
+                    for (id, messages) in failed {
+                        res.push_str(&format!(
+                            "\nFailed to download exercise: '{}'",
+                            id.exercise_slug
+                        ));
+                        for message in messages {
+                            res.push_str(&format!("\n    with message: '{message}'"));
+                        }
+                    }
+
+                    if !downloaded.is_empty() {
+                        res.push_str(&format!(
+                            "\n\nSuccessful downloads saved to {}",
+                            projects_dir.display()
+                        ));
+                    }
+
+                    anyhow::bail!(res);
                 }
             }
         }
-        Err(err) => anyhow::bail!(err),
+        Err(err) => {
+            manager.force_join();
+            anyhow::bail!(err);
+        }
     }
 
     Ok(format!(
@@ -172,6 +378,42 @@ pub fn download_exercises(
     ))
 }
 
+fn slugs(exercises: &[ExerciseDownload]) -> String {
+    exercises
+        .iter()
+        .map(|exercise| exercise.exercise_slug.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Summarizes the result of a download or an update for the JSON output
+pub fn download_summary(
+    downloaded: &[ExerciseDownload],
+    skipped: &[ExerciseDownload],
+    failed: &[(ExerciseDownload, Vec<String>)],
+) -> serde_json::Value {
+    let slugs = |exercises: &[ExerciseDownload]| {
+        exercises
+            .iter()
+            .map(|exercise| exercise.exercise_slug.clone())
+            .collect::<Vec<_>>()
+    };
+    let failed = failed
+        .iter()
+        .map(|(exercise, messages)| {
+            serde_json::json!({
+                "exercise": exercise.exercise_slug,
+                "messages": messages,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "downloaded": slugs(downloaded),
+        "skipped": slugs(skipped),
+        "failed": failed,
+    })
+}
+
 pub fn elevated_download(
     io: &mut Io,
     client: &mut Client,
@@ -191,12 +433,25 @@ pub fn elevated_download(
     let name_select = &vec[1];
 
     // Get course by name
-    let course = match util::get_course_by_name(client, name_select, org)? {
-        Some(course) => course,
-        None => anyhow::bail!("Could not find course with that name"),
-    };
+    let course = util::find_course(client, name_select, org)?;
     io.println("", PrintColor::Normal)?;
-    let msg = download_exercises(path, client, &course)?;
+    // the exercises chosen with --select, empty if all exercises are downloaded
+    let exercise_ids = vec
+        .get(2)
+        .map(|ids| {
+            ids.split(',')
+                .filter(|id| !id.is_empty())
+                .map(str::parse::<u32>)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .context("Invalid exercise ids")?
+        .unwrap_or_default();
+    let msg = if exercise_ids.is_empty() {
+        download_exercises(io, path, client, &course)?
+    } else {
+        download_exercise_ids(io, path, client, &exercise_ids)?
+    };
     io.println(&msg, PrintColor::Success)?;
     pause()?;
     Ok(())
@@ -212,3 +467,100 @@ fn pause() -> anyhow::Result<()> {
     stdin.read_line(&mut s)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(name: &str, deadline: Option<&str>) -> CourseExercise {
+        CourseExercise {
+            id: 0,
+            available_points: vec![],
+            awarded_points: vec![],
+            name: name.to_string(),
+            publish_time: None,
+            solution_visible_after: None,
+            deadline: deadline.map(str::to_string),
+            soft_deadline: None,
+            disabled: false,
+            unlocked: true,
+        }
+    }
+
+    #[test]
+    fn glob_match_test() {
+        assert!(glob_match("part01-*", "part01-01_hello"));
+        assert!(glob_match("*hello*", "part01-01_hello"));
+        assert!(glob_match("part0?-01*", "part02-01_world"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("part01-*", "part02-01_world"));
+        assert!(!glob_match("hello", "part01-01_hello"));
+        assert!(!glob_match("a*b", "aXbY"));
+    }
+
+    #[test]
+    fn exercise_filter_test() {
+        let exercises = vec![
+            exercise("part01-01_hello", Some("2024-01-14T23:59:00+02:00")),
+            exercise("part01-02_world", None),
+            exercise("part02-01_input", Some("2024-01-21T23:59:00+02:00")),
+            exercise("part10-01_final", Some("2024-03-01T23:59:00+02:00")),
+            exercise("osa01-01", None),
+        ];
+        let names = |filter: ExerciseFilter| {
+            filter
+                .apply(exercises.clone())
+                .into_iter()
+                .map(|exercise| exercise.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(ExerciseFilter::default()).len(), 5);
+        assert_eq!(
+            names(ExerciseFilter {
+                part: Some(1),
+                ..Default::default()
+            }),
+            ["part01-01_hello", "part01-02_world"]
+        );
+        assert_eq!(
+            names(ExerciseFilter {
+                part: Some(10),
+                ..Default::default()
+            }),
+            ["part10-01_final"]
+        );
+        assert_eq!(
+            names(ExerciseFilter {
+                due_before: Some(ExerciseFilter::parse_date("2024-01-22T00:00:00+02:00").unwrap()),
+                ..Default::default()
+            }),
+            ["part01-01_hello", "part02-01_input"]
+        );
+        assert_eq!(
+            names(ExerciseFilter {
+                pattern: Some("*-01_*".to_string()),
+                due_before: Some(ExerciseFilter::parse_date("2024-01-22T00:00:00+02:00").unwrap()),
+                ..Default::default()
+            }),
+            ["part01-01_hello", "part02-01_input"]
+        );
+        assert!(ExerciseFilter::parse_date("2024-01-22").is_ok());
+        assert!(ExerciseFilter::parse_date("next week").is_err());
+    }
+
+    #[test]
+    fn exercise_labels_test() {
+        let exercises = [
+            exercise("part01-01_hello", None),
+            exercise("part01-02_world", Some("next week")),
+            exercise("part02-01", Some("2024-01-02T14:31:00+00:00")),
+        ];
+
+        let labels = exercise_labels(&exercises);
+        assert_eq!(labels[0], "part01-01_hello");
+        assert_eq!(labels[1], "part01-02_world  deadline next week");
+        assert!(labels[2].starts_with("part02-01        deadline 2024-01-0"));
+    }
+}
@@ -19,12 +19,15 @@ pub fn list_exercises(
         fetched_course_name = choose_course(io, client, org)?;
         &fetched_course_name
     };
-    let course = util::get_course_by_name(client, name_select, org)?
-        .ok_or_else(|| anyhow::anyhow!("Could not find a course with name '{}'", name_select))?;
+    let course = util::find_course(client, name_select, org)?;
 
     let mut exercises = client.get_course_exercises(course.id)?;
     exercises.sort_unstable_by(|l, r| l.name.cmp(&r.name));
-    print_exercises(io, name_select, &exercises)?;
+    io.print_json(&serde_json::json!({
+        "course": course.name,
+        "exercises": exercises,
+    }))?;
+    print_exercises(io, &course.name, &exercises)?;
     Ok(())
 }
 
@@ -68,31 +71,84 @@ fn print_exercises(
             prev_soft_deadline = none;
         }
 
-        let mut completed = true;
-        let mut attempted = false;
+        io.println(
+            &format!("  {}: {}", completion(exercise).label(), &exercise.name),
+            PrintColor::Normal,
+        )?;
+    }
+    Ok(())
+}
 
-        for point in &exercise.available_points {
-            if !exercise.awarded_points.contains(&point.name) {
-                completed = false;
-            } else {
-                attempted = true;
-            }
+/// How many of the available points of an exercise have been awarded
+pub enum Completion {
+    Completed,
+    Attempted,
+    NotCompleted,
+}
+
+impl Completion {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Completed => "Completed",
+            Self::Attempted => "Attempted",
+            Self::NotCompleted => "Not completed",
         }
+    }
+}
+
+/// Checks the completion of an exercise from its available and awarded points
+pub fn completion(exercise: &CourseExercise) -> Completion {
+    let mut completed = true;
+    let mut attempted = false;
 
-        let completion_status = if completed {
-            "Completed"
-        } else if attempted {
-            "Attempted"
+    for point in &exercise.available_points {
+        if !exercise.awarded_points.contains(&point.name) {
+            completed = false;
         } else {
-            "Not completed"
-        };
+            attempted = true;
+        }
+    }
 
-        io.println(
-            &format!("  {}: {}", completion_status, &exercise.name),
-            PrintColor::Normal,
-        )?;
+    if completed {
+        Completion::Completed
+    } else if attempted {
+        Completion::Attempted
+    } else {
+        Completion::NotCompleted
     }
-    Ok(())
+}
+
+/// The deadlines, points and completion of an exercise for the details pane of the exercise menu
+pub fn describe_exercise(exercise: &CourseExercise) -> String {
+    let deadline = |deadline: &Option<String>| {
+        deadline
+            .as_deref()
+            .map(util::format_deadline)
+            .unwrap_or_else(|| "none".to_string())
+    };
+    let points = exercise
+        .available_points
+        .iter()
+        .map(|point| {
+            if exercise.awarded_points.contains(&point.name) {
+                format!("{} (awarded)", point.name)
+            } else {
+                point.name.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    let completion = completion(exercise).label();
+    format!(
+        "{}\nDeadline: {}\nSoft deadline: {}\nPoints: {}\nStatus: {completion}",
+        exercise.name,
+        deadline(&exercise.deadline),
+        deadline(&exercise.soft_deadline),
+        if points.is_empty() {
+            "none".to_string()
+        } else {
+            points.join(", ")
+        },
+    )
 }
 
 #[cfg(test)]
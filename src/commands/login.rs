@@ -2,50 +2,70 @@ use super::{download, organization, util};
 use crate::{
     client::Client,
     config::TmcCliConfig,
+    error::CliError,
     io::{Io, PrintColor},
 };
 use anyhow::Context;
+use std::env;
+
+const USERNAME_ENV: &str = "TMC_USERNAME";
+const PASSWORD_ENV: &str = "TMC_PASSWORD";
+
+/// How the user is authenticated
+pub enum LoginMethod {
+    /// The username and password are asked from the user unless they are given
+    /// as arguments, through stdin or in the TMC_USERNAME and TMC_PASSWORD environment variables.
+    Password {
+        username: Option<String>,
+        password_stdin: bool,
+    },
+    /// An existing OAuth access token, read from stdin if not given
+    Token { token: Option<String> },
+}
 
+/// Logs in and selects the organization.
+/// If organization is given, it is selected without asking and no courses are downloaded.
 pub fn login(
     io: &mut Io,
     client: &mut Client,
     interactive_mode: bool,
     config: &mut TmcCliConfig,
+    method: LoginMethod,
+    organization: Option<&str>,
 ) -> anyhow::Result<()> {
-    io.print("Email / username: ", PrintColor::Normal)?;
-    let mut username = io.read_line()?;
-    username = username.trim().to_string();
-
-    if username.is_empty() {
-        anyhow::bail!("Username cannot be empty!");
-    }
-
-    io.print("Password: ", PrintColor::Normal)?;
-
-    // Read password without rpassword if ran in --testmode, because rpassword
-    // is not able to read mock stdin input in binary tests
-    let mut password = if client.is_test_mode() {
-        io.read_line()?
-    } else {
-        io.read_password()?
+    let message = match method {
+        LoginMethod::Password {
+            username,
+            password_stdin,
+        } => login_with_password(io, client, config, username, password_stdin)?,
+        LoginMethod::Token { token } => {
+            let token = match token {
+                Some(token) => token,
+                None => io.read_line()?,
+            };
+            client.login_with_token(token.trim().to_string(), config)?
+        }
     };
-    password = password.trim().to_string();
-
-    let message = client.try_login(username, password, config)?;
     io.println(&message, PrintColor::Success)?;
 
-    let org = if interactive_mode {
+    let org = if let Some(slug) = organization {
+        organization::set_organization_with_slug(client, config, slug)
+    } else if interactive_mode {
         organization::set_organization(io, client, config)
     } else {
         organization::set_organization_old(io, client, config)
     }
     .context("Could not set organization")?;
+    io.print_json(&serde_json::json!({
+        "message": message,
+        "organization": org,
+    }))?;
 
     if client.is_test_mode() {
         return Ok(());
     }
 
-    if interactive_mode {
+    if interactive_mode && organization.is_none() {
         download_after_login(client, io, config, &org.slug)?;
     }
 
@@ -53,6 +73,47 @@ pub fn login(
     Ok(())
 }
 
+fn login_with_password(
+    io: &mut Io,
+    client: &mut Client,
+    config: &mut TmcCliConfig,
+    username: Option<String>,
+    password_stdin: bool,
+) -> anyhow::Result<String> {
+    let username = match username.or_else(|| env::var(USERNAME_ENV).ok()) {
+        Some(username) => username,
+        None => {
+            io.print("Email / username: ", PrintColor::Normal)?;
+            io.read_line()?
+        }
+    };
+    let username = username.trim().to_string();
+
+    if username.is_empty() {
+        anyhow::bail!("Username cannot be empty!");
+    }
+
+    let password = if password_stdin {
+        let password = io.read_line()?;
+        password.trim_end_matches(['\r', '\n']).to_string()
+    } else if let Ok(password) = env::var(PASSWORD_ENV) {
+        password
+    } else {
+        io.print("Password: ", PrintColor::Normal)?;
+
+        // Read password without rpassword if ran in --testmode, because rpassword
+        // is not able to read mock stdin input in binary tests
+        let password = if client.is_test_mode() {
+            io.read_line()?
+        } else {
+            io.read_password()?
+        };
+        password.trim().to_string()
+    };
+
+    client.try_login(username, password, config)
+}
+
 pub fn download_after_login(
     client: &mut Client,
     io: &mut Io,
@@ -62,10 +123,7 @@ pub fn download_after_login(
     io.println("Fetching courses...", PrintColor::Normal)?;
     let courses = client.list_courses(org)?;
 
-    let mut courses = courses
-        .iter()
-        .map(|course| client.get_course_details(course.id))
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut courses = util::get_courses_details(io, client, &courses)?;
 
     courses.sort_by(|a, b| {
         a.course
@@ -74,16 +132,11 @@ pub fn download_after_login(
             .cmp(&b.course.title.to_lowercase())
     });
 
-    let mut courses_displayed = courses
-        .iter()
-        .map(|course| course.course.title.as_str())
-        .collect::<Vec<_>>();
     let no_download = "Don't download anything".to_string();
-    courses_displayed.insert(0, no_download.as_str());
 
-    let course = util::get_course_name(&courses_displayed)?;
+    let course = util::get_course_name(io, &courses, &[&no_download])?;
     if course == no_download {
-        anyhow::bail!("No course downloaded.");
+        return Err(CliError::Cancelled("No course downloaded.".to_string()).into());
     }
     let name_select = &courses
         .iter()
@@ -93,11 +146,10 @@ pub fn download_after_login(
         .name;
 
     // Get course by name
-    let course = util::get_course_by_name(client, name_select, org)?
-        .ok_or_else(|| anyhow::anyhow!("Could not find course with that name"))?;
+    let course = util::find_course(client, name_select, org)?;
     let path = config.get_projects_dir();
 
-    let msg = download::download_exercises(path, client, &course)?;
+    let msg = download::download_exercises(io, path, client, &course)?;
     io.println(&msg, PrintColor::Success)?;
     Ok(())
 }
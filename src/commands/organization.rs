@@ -1,6 +1,7 @@
 use crate::{
     client::Client,
     config::TmcCliConfig,
+    error::CliError,
     interactive::{self, interactive_list},
     io::{Io, PrintColor},
 };
@@ -34,15 +35,25 @@ pub fn set_organization_old(
         "\nChoose organization by writing its slug: ",
         PrintColor::Normal,
     )?;
-    let slug = io.read_line()?.trim().to_string();
+    let slug = io.read_line()?;
 
+    set_organization_with_slug(client, config, slug.trim())
+}
+
+/// Saves the organization with the given slug without asking the user
+pub fn set_organization_with_slug(
+    client: &mut Client,
+    config: &mut TmcCliConfig,
+    slug: &str,
+) -> anyhow::Result<Organization> {
+    let orgs = client.get_organizations()?;
     if let Some(org) = orgs.into_iter().find(|org| org.slug == slug) {
         config.set_organization(org.slug.clone());
         config.save()?;
         return Ok(org);
     }
 
-    anyhow::bail!("No such organization for the given slug: {}", slug);
+    Err(CliError::NotFound(format!("No such organization for the given slug: {slug}")).into())
 }
 
 pub fn set_organization(
@@ -53,22 +64,29 @@ pub fn set_organization(
     io.println("Fetching organizations...", PrintColor::Normal)?;
     let mut orgs = client.get_organizations()?;
     orgs.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(a.name.cmp(&b.name)));
-    let mut pinned = orgs
+    let pinned_orgs = orgs.iter().filter(|org| org.pinned).collect::<Vec<_>>();
+    let pinned = pinned_orgs
         .iter()
-        .filter(|org| org.pinned)
         .map(|org| org.name.as_str())
         .collect::<Vec<_>>();
+    let pinned_details = |index: usize| pinned_orgs[index].information.clone();
 
     let others = String::from("View all organizations");
-    pinned.push(others.as_str());
 
     let prompt = String::from("Select your organization: ");
-    let selection = interactive::interactive_list(&prompt, &pinned)?
-        .ok_or_else(|| anyhow::anyhow!("Didn't select any organization"))?;
+    let selection = interactive::interactive_list_with_pinned(
+        io,
+        &prompt,
+        &pinned,
+        &[&others],
+        Some(&pinned_details),
+    )?
+    .ok_or_else(|| CliError::Cancelled("Didn't select any organization".to_string()))?;
     let org_name = if selection == others {
         let all = orgs.iter().map(|org| org.name.as_str()).collect::<Vec<_>>();
-        interactive_list(&prompt, &all)?
-            .ok_or_else(|| anyhow::anyhow!("Didn't select any organization"))?
+        let details = |index: usize| orgs[index].information.clone();
+        interactive_list(io, &prompt, &all, Some(&details))?
+            .ok_or_else(|| CliError::Cancelled("Didn't select any organization".to_string()))?
     } else {
         selection
     };
@@ -95,6 +113,7 @@ pub fn organization(
         set_organization_old(io, client, config)?
     };
 
+    io.print_json(&org)?;
     io.println(
         &format!("Selected {} as organization.", org.name),
         PrintColor::Success,
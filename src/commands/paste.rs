@@ -24,7 +24,7 @@ pub fn paste(
 ) -> anyhow::Result<()> {
     // todo: use context
     let exercise_path =
-        util::exercise_pathfinder(path, config).context("Error finding exercise")?;
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
 
     let (project_config, course_slug, exercise_slug) = util::parse_exercise_dir(exercise_path)?;
 
@@ -50,13 +50,14 @@ pub fn paste(
     );
 
     match new_submission {
-        Ok(_submission) => {
+        Ok(submission) => {
             manager.join();
+            io.print_json(&submission)?;
+            Ok(())
         }
         Err(err) => {
             manager.force_join();
-            io.println(&format!("Error: {err} "), PrintColor::Failed)?;
+            Err(err.context("Failed to send the paste"))
         }
     }
-    Ok(())
 }
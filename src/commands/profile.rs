@@ -0,0 +1,175 @@
+use super::util;
+use crate::{
+    cli::ProfileCommand,
+    config::{self, Profile, TmcCliConfig, DEFAULT_PROFILE},
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use reqwest::Url;
+
+/// Adds, lists, selects and removes the saved server profiles
+pub fn profile(
+    io: &mut Io,
+    command: ProfileCommand,
+    config: &mut TmcCliConfig,
+) -> anyhow::Result<()> {
+    match command {
+        ProfileCommand::Add {
+            name,
+            root_url,
+            organization,
+        } => add(io, config, name, root_url, organization),
+        ProfileCommand::List => list(io, config),
+        ProfileCommand::Use { name } => use_profile(io, config, name),
+        ProfileCommand::Remove { name } => remove(io, config, &name),
+    }
+}
+
+fn add(
+    io: &mut Io,
+    config: &mut TmcCliConfig,
+    name: String,
+    root_url: String,
+    organization: Option<String>,
+) -> anyhow::Result<()> {
+    validate_name(&name)?;
+    if config.get_profile(&name).is_some() {
+        anyhow::bail!(
+            "Profile '{name}' already exists. Remove it first with 'tmc profile remove {name}'"
+        );
+    }
+    root_url
+        .parse::<Url>()
+        .with_context(|| format!("Failed to parse {root_url} as a URL"))?;
+
+    let profile = Profile {
+        root_url,
+        organization,
+    };
+    config.set_profile(name.clone(), &profile);
+    config.save()?;
+
+    io.print_json(&serde_json::json!({
+        "name": name,
+        "root_url": profile.root_url,
+        "organization": profile.organization,
+    }))?;
+    io.println(
+        &format!("Added profile {name}. Log in with 'tmc --profile {name} login'"),
+        PrintColor::Success,
+    )?;
+    Ok(())
+}
+
+fn list(io: &mut Io, config: &TmcCliConfig) -> anyhow::Result<()> {
+    let profiles = config.get_profiles();
+    let selected = config.selected_profile().unwrap_or(DEFAULT_PROFILE);
+
+    io.print_json(
+        &profiles
+            .iter()
+            .map(|(name, profile)| {
+                serde_json::json!({
+                    "name": name,
+                    "root_url": profile.root_url,
+                    "organization": profile.organization,
+                    "selected": name == selected,
+                })
+            })
+            .collect::<Vec<_>>(),
+    )?;
+
+    let width = profiles
+        .keys()
+        .map(|name| name.chars().count())
+        .chain([DEFAULT_PROFILE.chars().count()])
+        .max()
+        .unwrap_or_default();
+    let marker = |name: &str| if name == selected { "*" } else { " " };
+    let default_root_url = std::env::var("TMC_LANGS_TMC_ROOT_URL")
+        .unwrap_or_else(|_| "https://tmc.mooc.fi".to_string());
+    io.println(
+        &format!(
+            "{} {DEFAULT_PROFILE:width$}  {default_root_url}",
+            marker(DEFAULT_PROFILE)
+        ),
+        PrintColor::Normal,
+    )?;
+    for (name, profile) in &profiles {
+        let organization = profile
+            .organization
+            .as_deref()
+            .map(|organization| format!("  (organization: {organization})"))
+            .unwrap_or_default();
+        io.println(
+            &format!(
+                "{} {name:width$}  {}{organization}",
+                marker(name),
+                profile.root_url
+            ),
+            PrintColor::Normal,
+        )?;
+    }
+    Ok(())
+}
+
+fn use_profile(io: &mut Io, config: &mut TmcCliConfig, name: String) -> anyhow::Result<()> {
+    if name == DEFAULT_PROFILE {
+        config.set_active_profile(None);
+    } else {
+        config.get_profile(&name).with_context(|| {
+            format!("No profile named '{name}'. You can add one with 'tmc profile add'")
+        })?;
+        config.set_active_profile(Some(name.clone()));
+    }
+    config.save()?;
+
+    io.print_json(&serde_json::json!({ "profile": name }))?;
+    io.println(&format!("Using profile {name}"), PrintColor::Success)?;
+    Ok(())
+}
+
+fn remove(io: &mut Io, config: &mut TmcCliConfig, name: &str) -> anyhow::Result<()> {
+    if !config.remove_profile(name) {
+        anyhow::bail!("No profile named '{name}'");
+    }
+    config.save()?;
+    if let Some(credentials) = util::get_credentials(&config::credentials_name(Some(name))) {
+        credentials
+            .remove()
+            .context("Failed to remove the login of the profile")?;
+    }
+
+    io.print_json(&serde_json::json!({ "profile": name }))?;
+    io.println(&format!("Removed profile {name}"), PrintColor::Success)?;
+    Ok(())
+}
+
+/// Profile names are used in file names, so only simple names are allowed
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name == DEFAULT_PROFILE {
+        anyhow::bail!("The name '{DEFAULT_PROFILE}' is reserved for the built-in server");
+    }
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Invalid profile name '{name}'. Use only letters, numbers, '-' and '_'");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_name_test() {
+        assert!(validate_name("internal").is_ok());
+        assert!(validate_name("my_server-2").is_ok());
+        assert!(validate_name("default").is_err());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../evil").is_err());
+    }
+}
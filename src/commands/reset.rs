@@ -0,0 +1,159 @@
+use super::util;
+use crate::{
+    client::Client,
+    config::TmcCliConfig,
+    error::CliError,
+    io::{Io, PrintColor},
+    progress_reporting,
+    progress_reporting::ProgressBarManager,
+};
+use anyhow::Context;
+use std::{fs, path::Path};
+use tmc_langs::{tmc::ClientUpdateData, DownloadResult, ProjectsConfig};
+
+/// Restores an exercise to its original template from the server.
+/// The current files of the exercise are moved to a backup directory next to it first.
+/// Path to the exercise can be given as a parameter or
+/// the user can run the command in the exercise folder.
+///
+/// # Errors
+/// Returns an error if no exercise was found or the template could not be downloaded,
+/// in which case the original files are restored.
+pub fn reset(
+    io: &mut Io,
+    client: &mut Client,
+    path: Option<&str>,
+    skip_confirmation: bool,
+    config: &TmcCliConfig,
+) -> anyhow::Result<()> {
+    let exercise_path =
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
+    let (projects_dir, course_slug, exercise_slug) =
+        util::parse_exercise_dir(exercise_path.clone())?;
+
+    let projects_config = ProjectsConfig::load(&projects_dir).with_context(|| {
+        format!(
+            "Failed to load projects config from directory '{}'",
+            projects_dir.display(),
+        )
+    })?;
+    let exercise_id = util::exercise_id(&projects_config, &course_slug, &exercise_slug)?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let backup_path = exercise_path.with_file_name(format!("{exercise_slug}-backup-{timestamp}"));
+
+    if !skip_confirmation {
+        io.println(
+            &format!(
+                "All changes to {exercise_slug} will be reset. The current files are moved to {}",
+                backup_path.display()
+            ),
+            PrintColor::Normal,
+        )?;
+        io.print("Continue? [y/N] ", PrintColor::Normal)?;
+        let answer = io.read_line()?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            return Err(CliError::Cancelled("Reset cancelled".to_string()).into());
+        }
+    }
+
+    fs::rename(&exercise_path, &backup_path).with_context(|| {
+        format!(
+            "Failed to back up {} to {}",
+            exercise_path.display(),
+            backup_path.display()
+        )
+    })?;
+
+    if let Err(err) = download_template(client, exercise_id, &projects_dir) {
+        restore_backup(&backup_path, &exercise_path)?;
+        return Err(err.context("Failed to reset exercise, the original files were restored"));
+    }
+
+    io.print_json(&serde_json::json!({
+        "exercise": exercise_slug,
+        "path": exercise_path,
+        "backup": backup_path,
+    }))?;
+    io.println(
+        &format!(
+            "\nExercise {exercise_slug} was reset. The previous files were saved to {}",
+            backup_path.display()
+        ),
+        PrintColor::Success,
+    )?;
+    Ok(())
+}
+
+/// Downloads the template of an exercise that is no longer on disk
+fn download_template(
+    client: &mut Client,
+    exercise_id: u32,
+    projects_dir: &Path,
+) -> anyhow::Result<()> {
+    // start manager for 1 event: tmc_langs::download_or_update_exercises
+    let mut manager = ProgressBarManager::new(
+        progress_reporting::get_default_style(),
+        1,
+        client.is_test_mode(),
+    );
+    manager.start::<ClientUpdateData>();
+
+    match client.download_or_update_exercises(&[exercise_id], projects_dir) {
+        Ok(DownloadResult::Success { .. }) => {
+            manager.join();
+            Ok(())
+        }
+        Ok(DownloadResult::Failure { failed, .. }) => {
+            manager.join();
+            let messages = failed
+                .into_iter()
+                .flat_map(|(_, messages)| messages)
+                .collect::<Vec<_>>()
+                .join("\n");
+            anyhow::bail!("Failed to download exercise template: {messages}");
+        }
+        Err(err) => {
+            manager.force_join();
+            Err(anyhow::Error::new(err).context("Failed to download exercise template"))
+        }
+    }
+}
+
+fn restore_backup(backup_path: &Path, exercise_path: &Path) -> anyhow::Result<()> {
+    if exercise_path.exists() {
+        fs::remove_dir_all(exercise_path)
+            .with_context(|| format!("Failed to remove {}", exercise_path.display()))?;
+    }
+    fs::rename(backup_path, exercise_path).with_context(|| {
+        format!(
+            "Failed to restore the original files from {}",
+            backup_path.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_backup_replaces_partial_download_test() {
+        let temp = tempfile::tempdir().unwrap();
+        let exercise_path = temp.path().join("exercise");
+        let backup_path = temp.path().join("exercise-backup-20240101120000");
+        fs::create_dir_all(&backup_path).unwrap();
+        fs::write(backup_path.join("Main.java"), "my solution").unwrap();
+        fs::create_dir_all(&exercise_path).unwrap();
+        fs::write(exercise_path.join("partial"), "").unwrap();
+
+        restore_backup(&backup_path, &exercise_path).unwrap();
+
+        assert!(!backup_path.exists());
+        assert!(!exercise_path.join("partial").exists());
+        assert_eq!(
+            fs::read_to_string(exercise_path.join("Main.java")).unwrap(),
+            "my solution"
+        );
+    }
+}
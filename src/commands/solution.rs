@@ -0,0 +1,115 @@
+use super::util;
+use crate::{
+    client::Client,
+    config::TmcCliConfig,
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::path::Path;
+use tmc_langs::ProjectsConfig;
+
+/// Downloads the model solution of an exercise into a directory next to the exercise.
+/// The exercise itself is never modified.
+/// Path to the exercise can be given as a parameter or
+/// the user can run the command in the exercise folder.
+///
+/// # Errors
+/// Returns an error if the exercise was not found, the solution is not visible yet
+/// or the solution could not be downloaded.
+pub fn solution(
+    io: &mut Io,
+    client: &mut Client,
+    path: Option<&str>,
+    config: &TmcCliConfig,
+    org: &str,
+) -> anyhow::Result<()> {
+    let exercise_path =
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
+    let (projects_dir, course_slug, exercise_slug) =
+        util::parse_exercise_dir(exercise_path.clone())?;
+
+    let projects_config = ProjectsConfig::load(&projects_dir)
+        .context("Could not load info about the downloaded courses")?;
+    let exercise_id = util::exercise_id(&projects_config, &course_slug, &exercise_slug)?;
+
+    let solution_path = exercise_path.with_file_name(format!("{exercise_slug}-solution"));
+    if is_non_empty_dir(&solution_path) {
+        anyhow::bail!(
+            "The model solution has already been downloaded to {}. Remove the directory to download it again.",
+            solution_path.display()
+        );
+    }
+
+    let course = util::find_course(client, &course_slug, org)?;
+    let exercises = client.get_course_exercises(course.id)?;
+    let exercise = exercises
+        .iter()
+        .find(|exercise| exercise.id == exercise_id)
+        .with_context(|| format!("Exercise {exercise_slug} was not found on the server"))?;
+    if let Some(visible_after) =
+        hidden_until(exercise.solution_visible_after.as_deref(), Utc::now())
+    {
+        anyhow::bail!(
+            "The model solution of {exercise_slug} will be available on {}",
+            visible_after.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        );
+    }
+
+    io.println(
+        &format!("Downloading the model solution of {exercise_slug}..."),
+        PrintColor::Normal,
+    )?;
+    client
+        .download_model_solution(exercise_id, &solution_path)
+        .with_context(|| {
+            format!(
+                "Failed to download the model solution of {exercise_slug}. \
+                Solutions are usually available after you have completed the exercise."
+            )
+        })?;
+
+    io.print_json(&serde_json::json!({
+        "exercise": exercise_slug,
+        "path": solution_path,
+    }))?;
+    io.println(
+        &format!("Model solution downloaded to {}", solution_path.display()),
+        PrintColor::Success,
+    )?;
+    Ok(())
+}
+
+/// Returns the time the solution becomes visible if it is still in the future
+fn hidden_until(
+    solution_visible_after: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<FixedOffset>> {
+    solution_visible_after
+        .and_then(util::parse_deadline)
+        .filter(|visible_after| *visible_after > now)
+}
+
+fn is_non_empty_dir(path: &Path) -> bool {
+    path.read_dir()
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn hidden_until_test() {
+        let now = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+
+        assert!(hidden_until(None, now).is_none());
+        assert!(hidden_until(Some("2021-05-31T12:00:00+03:00"), now).is_none());
+        assert_eq!(
+            hidden_until(Some("2021-06-02T12:00:00+03:00"), now),
+            Some(DateTime::parse_from_rfc3339("2021-06-02T12:00:00+03:00").unwrap())
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use super::{
+    exercises::{self, Completion},
+    util,
+};
+use crate::{
+    client::Client,
+    config::TmcCliConfig,
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use tmc_langs::{tmc::response::CourseExercise, ProjectsConfig};
+
+/// Status of a single exercise, combined from the server and the projects directory
+struct ExerciseStatus<'a> {
+    exercise: &'a CourseExercise,
+    completion: Completion,
+    awarded_points: usize,
+    downloaded: bool,
+    deadline_passed: bool,
+}
+
+/// Shows the progress of the downloaded courses by combining the local exercises
+/// with the points and deadlines from the server.
+/// If course_name is given, only that course is shown.
+///
+/// # Errors
+/// Returns an error if no downloaded courses were found or the server could not be reached.
+pub fn status(
+    io: &mut Io,
+    client: &mut Client,
+    course_name: Option<&str>,
+    config: &TmcCliConfig,
+    org: &str,
+) -> anyhow::Result<()> {
+    let projects_dir = config.get_projects_dir();
+    let projects_config = ProjectsConfig::load(projects_dir)
+        .context("Could not load info about the downloaded courses")?;
+    if projects_config.courses.is_empty() {
+        anyhow::bail!(
+            "No downloaded courses found. Project directory set to {}",
+            projects_dir.display()
+        );
+    }
+
+    io.println("Fetching courses...", PrintColor::Normal)?;
+    let courses = client.list_courses(org)?;
+    let selected = course_name
+        .map(|name| util::find_downloaded_course(&projects_config, &courses, name))
+        .transpose()?;
+    let local_courses = projects_config
+        .courses
+        .iter()
+        .filter(|(slug, _)| selected.map_or(true, |selected| selected == slug.as_str()))
+        .collect::<Vec<_>>();
+    let now = Utc::now();
+
+    let mut json = Vec::new();
+    for (course_slug, course_config) in local_courses {
+        let Some(course) = courses.iter().find(|course| &course.name == course_slug) else {
+            io.println(
+                &format!("\nCourse {course_slug} was not found in the selected organization"),
+                PrintColor::Failed,
+            )?;
+            continue;
+        };
+        let mut exercises = client.get_course_exercises(course.id)?;
+        exercises.sort_unstable_by(|l, r| l.name.cmp(&r.name));
+
+        let downloaded = course_config
+            .exercises
+            .keys()
+            .map(String::as_str)
+            .collect::<HashSet<_>>();
+        let statuses = exercise_statuses(&exercises, &downloaded, now);
+        json.push(serde_json::json!({
+            "course": course_slug,
+            "exercises": statuses
+                .iter()
+                .map(|status| serde_json::json!({
+                    "id": status.exercise.id,
+                    "name": status.exercise.name,
+                    "completion": status.completion.label(),
+                    "awarded_points": status.awarded_points,
+                    "available_points": status.exercise.available_points.len(),
+                    "downloaded": status.downloaded,
+                    "deadline": status.exercise.deadline,
+                    "deadline_passed": status.deadline_passed,
+                    "unlocked": status.exercise.unlocked,
+                }))
+                .collect::<Vec<_>>(),
+        }));
+        print_course_status(io, course_slug, &statuses)?;
+    }
+    io.print_json(&json)?;
+    Ok(())
+}
+
+fn exercise_statuses<'a>(
+    exercises: &'a [CourseExercise],
+    downloaded: &HashSet<&str>,
+    now: DateTime<Utc>,
+) -> Vec<ExerciseStatus<'a>> {
+    exercises
+        .iter()
+        // Skip disabled exercises
+        .filter(|exercise| !exercise.disabled)
+        .map(|exercise| {
+            let completion = exercises::completion(exercise);
+            let awarded_points = exercise
+                .available_points
+                .iter()
+                .filter(|point| exercise.awarded_points.contains(&point.name))
+                .count();
+            let deadline_passed = !matches!(completion, Completion::Completed)
+                && exercise
+                    .deadline
+                    .as_deref()
+                    .and_then(util::parse_deadline)
+                    .is_some_and(|deadline| deadline < now);
+            ExerciseStatus {
+                exercise,
+                completion,
+                awarded_points,
+                downloaded: downloaded.contains(exercise.name.as_str()),
+                deadline_passed,
+            }
+        })
+        .collect()
+}
+
+/// Prints the status of each exercise of a course and the point totals of the course
+fn print_course_status(
+    io: &mut Io,
+    course_name: &str,
+    statuses: &[ExerciseStatus],
+) -> anyhow::Result<()> {
+    io.println("", PrintColor::Normal)?;
+    io.println(&format!("Course: {course_name}"), PrintColor::Normal)?;
+
+    let width = statuses
+        .iter()
+        .map(|status| status.exercise.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    for status in statuses {
+        let mut notes = Vec::new();
+        if !status.exercise.unlocked {
+            notes.push("locked");
+        } else if !status.downloaded {
+            notes.push("not downloaded");
+        }
+        if status.deadline_passed {
+            notes.push("deadline passed");
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!("  ({})", notes.join(", "))
+        };
+
+        let color = if status.deadline_passed {
+            PrintColor::Failed
+        } else if matches!(status.completion, Completion::Completed) {
+            PrintColor::Success
+        } else {
+            PrintColor::Normal
+        };
+        io.println(
+            &format!(
+                "  {:<13}  {:width$}  {}/{} points{notes}",
+                status.completion.label(),
+                status.exercise.name,
+                status.awarded_points,
+                status.exercise.available_points.len(),
+            ),
+            color,
+        )?;
+    }
+
+    let completed = statuses
+        .iter()
+        .filter(|status| matches!(status.completion, Completion::Completed))
+        .count();
+    let awarded_points = statuses
+        .iter()
+        .map(|status| status.awarded_points)
+        .sum::<usize>();
+    let available_points = statuses
+        .iter()
+        .map(|status| status.exercise.available_points.len())
+        .sum::<usize>();
+    io.println(
+        &format!(
+            "Points: {awarded_points}/{available_points}, completed exercises: {completed}/{}",
+            statuses.len()
+        ),
+        PrintColor::Normal,
+    )?;
+    io.println(
+        &util::get_progress_string(awarded_points, available_points, 64),
+        PrintColor::Normal,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn exercise(name: &str, deadline: Option<&str>, unlocked: bool) -> CourseExercise {
+        CourseExercise {
+            id: 0,
+            available_points: vec![],
+            awarded_points: vec![],
+            name: name.to_string(),
+            publish_time: None,
+            solution_visible_after: None,
+            deadline: deadline.map(str::to_string),
+            soft_deadline: None,
+            disabled: false,
+            unlocked,
+        }
+    }
+
+    #[test]
+    fn print_course_status_test() {
+        let (mut input, mut output) = crate::test_helper::input_output();
+        let mut io = Io::new(&mut output, &mut input);
+
+        let exercises = [
+            exercise("part01-01_downloaded", None, true),
+            exercise("part01-02_not_downloaded", None, true),
+            exercise("part01-03_locked", None, false),
+        ];
+        let downloaded = HashSet::from(["part01-01_downloaded"]);
+        let now = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let statuses = exercise_statuses(&exercises, &downloaded, now);
+
+        print_course_status(&mut io, "course_name", &statuses).unwrap();
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        let output = output.lines().collect::<Vec<_>>();
+        assert_eq!(output[0], "");
+        assert_eq!(output[1], "Course: course_name");
+        assert_eq!(
+            output[2],
+            "  Completed      part01-01_downloaded      0/0 points"
+        );
+        assert_eq!(
+            output[3],
+            "  Completed      part01-02_not_downloaded  0/0 points  (not downloaded)"
+        );
+        assert_eq!(
+            output[4],
+            "  Completed      part01-03_locked          0/0 points  (locked)"
+        );
+        assert_eq!(output[5], "Points: 0/0, completed exercises: 3/3");
+    }
+
+    fn unfinished_exercise(name: &str, deadline: &str) -> CourseExercise {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "available_points": [
+                {
+                    "id": 1,
+                    "exercise_id": 1,
+                    "name": "1.1",
+                    "requires_review": false,
+                },
+            ],
+            "awarded_points": [],
+            "name": name,
+            "deadline": deadline,
+            "disabled": false,
+            "unlocked": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn deadline_passed_test() {
+        let exercises = [
+            unfinished_exercise("past", "2020-12-31T23:59:59+02:00"),
+            unfinished_exercise("future", "2021-01-31T23:59:59+02:00"),
+            unfinished_exercise("invalid", "not a date"),
+        ];
+        let downloaded = HashSet::new();
+        let now = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+
+        let statuses = exercise_statuses(&exercises, &downloaded, now);
+
+        assert!(statuses[0].deadline_passed);
+        assert!(!statuses[1].deadline_passed);
+        assert!(!statuses[2].deadline_passed);
+        assert!(matches!(statuses[0].completion, Completion::NotCompleted));
+    }
+}
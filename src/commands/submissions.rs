@@ -0,0 +1,217 @@
+use super::util;
+use crate::{
+    client::Client,
+    config::TmcCliConfig,
+    io::{Io, PrintColor},
+};
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset, Local};
+use tmc_langs::{tmc::response::Submission, ProjectsConfig};
+
+/// A previous submission as shown to the user
+struct SubmissionEntry {
+    exercise: String,
+    created_at: DateTime<FixedOffset>,
+    status: SubmissionStatus,
+    points: Vec<String>,
+    url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubmissionStatus {
+    Processing,
+    Error,
+    Passed,
+    Failed,
+}
+
+impl SubmissionStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Processing => "Processing",
+            Self::Error => "Error",
+            Self::Passed => "Passed",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// Lists the previous submissions of the user, either for a single exercise or a whole course.
+/// Path to the exercise can be given as a parameter or
+/// the user can run the command in the exercise folder.
+///
+/// # Errors
+/// Returns an error if the exercise or course was not found or the server could not be reached.
+pub fn submissions(
+    io: &mut Io,
+    client: &mut Client,
+    exercise: Option<&str>,
+    course: Option<&str>,
+    config: &TmcCliConfig,
+    org: Option<&str>,
+) -> anyhow::Result<()> {
+    let (title, submissions) = if let Some(course_name) = course {
+        let org = org.context(
+            "No organization selected. You can select an organization with the `organization` command.",
+        )?;
+        let course = util::find_course(client, course_name, org)?;
+        (
+            format!("Course: {}", course.name),
+            client.get_course_submissions(course.id)?,
+        )
+    } else {
+        let exercise_path =
+            util::exercise_pathfinder(io, exercise, config).context("Error finding exercise")?;
+        let (projects_dir, course_slug, exercise_slug) = util::parse_exercise_dir(exercise_path)?;
+        let projects_config = ProjectsConfig::load(&projects_dir)
+            .context("Could not load info about the downloaded courses")?;
+        let exercise_id = util::exercise_id(&projects_config, &course_slug, &exercise_slug)?;
+        (
+            format!("Exercise: {exercise_slug}"),
+            client.get_exercise_submissions(exercise_id)?,
+        )
+    };
+
+    let mut entries = submissions
+        .into_iter()
+        .map(|submission| to_entry(client, submission))
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+
+    io.print_json(
+        &entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "exercise": entry.exercise,
+                    "created_at": entry.created_at.to_rfc3339(),
+                    "status": entry.status.label(),
+                    "points": entry.points,
+                    "url": entry.url,
+                })
+            })
+            .collect::<Vec<_>>(),
+    )?;
+    print_submissions(io, &title, &entries, course.is_some())
+}
+
+fn to_entry(client: &Client, submission: Submission) -> SubmissionEntry {
+    let status = if !submission.processed {
+        SubmissionStatus::Processing
+    } else if submission.pretest_error.is_some() {
+        SubmissionStatus::Error
+    } else if submission.all_tests_passed {
+        SubmissionStatus::Passed
+    } else {
+        SubmissionStatus::Failed
+    };
+    let points = submission
+        .points
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    SubmissionEntry {
+        url: client.submission_url(submission.id),
+        exercise: submission.exercise_name,
+        created_at: submission.created_at,
+        status,
+        points,
+    }
+}
+
+fn print_submissions(
+    io: &mut Io,
+    title: &str,
+    entries: &[SubmissionEntry],
+    show_exercise: bool,
+) -> anyhow::Result<()> {
+    io.println(title, PrintColor::Normal)?;
+    if entries.is_empty() {
+        io.println("No submissions found", PrintColor::Normal)?;
+        return Ok(());
+    }
+
+    let exercise_width = entries
+        .iter()
+        .map(|entry| entry.exercise.chars().count())
+        .max()
+        .unwrap_or_default();
+    for entry in entries {
+        let exercise = if show_exercise {
+            format!("  {:exercise_width$}", entry.exercise)
+        } else {
+            String::new()
+        };
+        let points = if entry.points.is_empty() {
+            "-".to_string()
+        } else {
+            entry.points.join(", ")
+        };
+        let color = match entry.status {
+            SubmissionStatus::Passed => PrintColor::Success,
+            SubmissionStatus::Failed | SubmissionStatus::Error => PrintColor::Failed,
+            SubmissionStatus::Processing => PrintColor::Normal,
+        };
+        io.println(
+            &format!(
+                "  {}{exercise}  {:<10}  points: {points}  {}",
+                entry
+                    .created_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M"),
+                entry.status.label(),
+                entry.url,
+            ),
+            color,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn print_submissions_test() {
+        let (mut input, mut output) = crate::test_helper::input_output();
+        let mut io = Io::new(&mut output, &mut input);
+
+        let created_at = Local::now().fixed_offset();
+        let timestamp = created_at.format("%Y-%m-%d %H:%M");
+        let entries = [
+            SubmissionEntry {
+                exercise: "part01-01_hello".to_string(),
+                created_at,
+                status: SubmissionStatus::Passed,
+                points: vec!["1.1".to_string(), "1.2".to_string()],
+                url: "https://tmc.mooc.fi/submissions/2".to_string(),
+            },
+            SubmissionEntry {
+                exercise: "part01-02_hi".to_string(),
+                created_at,
+                status: SubmissionStatus::Failed,
+                points: vec![],
+                url: "https://tmc.mooc.fi/submissions/1".to_string(),
+            },
+        ];
+        print_submissions(&mut io, "Course: test-course", &entries, true).unwrap();
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        let output = output.lines().collect::<Vec<_>>();
+        assert_eq!(output[0], "Course: test-course");
+        assert_eq!(
+            output[1],
+            format!(
+                "  {timestamp}  part01-01_hello  Passed      points: 1.1, 1.2  https://tmc.mooc.fi/submissions/2"
+            )
+        );
+        assert_eq!(
+            output[2],
+            format!(
+                "  {timestamp}  part01-02_hi     Failed      points: -  https://tmc.mooc.fi/submissions/1"
+            )
+        );
+    }
+}
@@ -1,7 +1,8 @@
-use super::util;
+use super::{test, util};
 use crate::{
     client::Client,
     config::TmcCliConfig,
+    error::{self, CliError},
     io::{Io, PrintColor},
     progress_reporting,
     progress_reporting::ProgressBarManager,
@@ -9,8 +10,11 @@ use crate::{
 use anyhow::{Context, Result};
 use reqwest::Url;
 use tmc_langs::{
-    tmc::{response::SubmissionFinished, ClientUpdateData},
-    Language,
+    tmc::{
+        response::{NewSubmission, SubmissionFinished},
+        ClientUpdateData,
+    },
+    Language, RunStatus,
 };
 
 /// Sends the course exercise submission to the server.
@@ -20,6 +24,7 @@ use tmc_langs::{
 /// # Errors
 /// Returns an error if no exercise was found on given path or current folder.
 /// Returns an error if user is not logged in.
+/// Returns `CliError::TestsFailed` if the tests did not pass on the server.
 pub fn submit(
     io: &mut Io,
     client: &mut Client,
@@ -30,7 +35,7 @@ pub fn submit(
 
     // todo: use context
     let exercise_path =
-        util::exercise_pathfinder(path, config).context("Error finding exercise")?;
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
 
     let (project_config, course_slug, exercise_slug) = util::parse_exercise_dir(exercise_path)?;
 
@@ -48,10 +53,10 @@ pub fn submit(
     let new_submission =
         match client.submit(&project_config, &course_slug, &exercise_slug, Some(locale)) {
             Ok(sub) => sub,
-            err @ Err(_) => {
+            Err(err) => {
                 manager.force_join();
 
-                return err.map(|_| ()).context("Error during submission");
+                return Err(error::from_langs_error(err)).context("Error during submission");
             }
         };
 
@@ -65,18 +70,232 @@ pub fn submit(
         Ok(submission_finished) => {
             manager.join();
 
+            io.print_json(&serde_json::json!({
+                "submission": new_submission,
+                "result": submission_finished,
+            }))?;
+            let all_passed = submission_finished.all_tests_passed == Some(true);
             print_wait_for_submission_results(io, submission_finished)?;
+            if !all_passed {
+                return Err(CliError::TestsFailed.into());
+            }
         }
         Err(err) => {
             manager.force_join();
 
-            io.println(&format!("Failed while waiting for server to process submission.\n You can still check your submission manually here: {}.", &new_submission.show_submission_url), PrintColor::Normal)?;
-            io.println(&format!("Error message: {err:#?}"), PrintColor::Normal)?;
+            io.print_json(&serde_json::json!({
+                "submission": new_submission,
+                "error": format!("{err:#}"),
+            }))?;
+            io.println(
+                &format!(
+                    "You can still check your submission manually here: {}.",
+                    &new_submission.show_submission_url
+                ),
+                PrintColor::Normal,
+            )?;
+            return Err(err.context("Failed while waiting for server to process submission"));
         }
     }
     Ok(())
 }
 
+/// Submission of one exercise in a batch submission
+struct BatchSubmission {
+    exercise_slug: String,
+    submission: Option<NewSubmission>,
+    result: anyhow::Result<SubmissionFinished>,
+}
+
+/// Runs the local tests of every downloaded exercise of a course and submits the exercises
+/// that pass them. Waits for the server to process all of the submissions and prints
+/// a summary of the awarded points.
+/// If course not given, the course of the current directory is used.
+///
+/// # Errors
+/// Returns an error if no course was found or none of the exercises passed the local tests.
+/// Returns the error of the first submission that failed, or `CliError::TestsFailed`
+/// if the tests of any of the submissions did not pass on the server.
+pub fn submit_passing(
+    io: &mut Io,
+    client: &mut Client,
+    course: Option<&str>,
+    config: &TmcCliConfig,
+) -> anyhow::Result<()> {
+    let locale = into_locale("fin").expect("The locale should always be valid.");
+
+    let (course_slug, exercise_dirs) =
+        util::course_exercise_dirs(course, config).context("Error finding course")?;
+
+    let mut passing = Vec::new();
+    for exercise_dir in exercise_dirs {
+        match test::test_exercise(io, &exercise_dir, false) {
+            Ok(run_result) => {
+                if matches!(run_result.status, RunStatus::Passed) {
+                    passing.push(exercise_dir);
+                }
+            }
+            Err(err) => {
+                io.println(
+                    &format!(
+                        "Failed to run tests for {}: {err:#}",
+                        exercise_dir.display()
+                    ),
+                    PrintColor::Failed,
+                )?;
+            }
+        }
+    }
+    if passing.is_empty() {
+        anyhow::bail!("None of the exercises of course '{course_slug}' passed the local tests");
+    }
+
+    io.println("", PrintColor::Normal)?;
+    let mut new_submissions = Vec::new();
+    for exercise_dir in passing {
+        let (projects_dir, course_slug, exercise_slug) = util::parse_exercise_dir(exercise_dir)?;
+        io.println(&format!("Submitting {exercise_slug}"), PrintColor::Normal)?;
+        let new_submission = client
+            .submit(&projects_dir, &course_slug, &exercise_slug, Some(locale))
+            .map_err(error::from_langs_error)
+            .context("Error during submission");
+        new_submissions.push((exercise_slug, new_submission));
+    }
+
+    io.println(
+        "Waiting for the server to process the submissions...",
+        PrintColor::Normal,
+    )?;
+    let mut submissions = Vec::new();
+    for (exercise_slug, new_submission) in new_submissions {
+        let batch_submission = match new_submission {
+            Ok(submission) => {
+                let result = Url::parse(&submission.submission_url)
+                    .context("Invalid submission URL")
+                    .and_then(|url| {
+                        client
+                            .wait_for_submission(url)
+                            .context("Failed while waiting for server to process submission")
+                    });
+                BatchSubmission {
+                    exercise_slug,
+                    submission: Some(submission),
+                    result,
+                }
+            }
+            Err(err) => BatchSubmission {
+                exercise_slug,
+                submission: None,
+                result: Err(err),
+            },
+        };
+        submissions.push(batch_submission);
+    }
+
+    print_batch_submission_results(io, &course_slug, &submissions)?;
+    batch_submission_outcome(submissions)
+}
+
+/// Fails with the first error of the submissions, or with `CliError::TestsFailed`
+/// if the tests of any submission did not pass on the server
+fn batch_submission_outcome(submissions: Vec<BatchSubmission>) -> anyhow::Result<()> {
+    let total = submissions.len();
+    let mut errors = Vec::new();
+    let mut failed_tests = 0;
+    for batch_submission in submissions {
+        match batch_submission.result {
+            Ok(result) if result.all_tests_passed == Some(true) => {}
+            Ok(_) => failed_tests += 1,
+            Err(err) => errors.push(err),
+        }
+    }
+
+    let failed = errors.len();
+    if let Some(err) = errors.into_iter().next() {
+        return Err(err.context(format!("{failed} of {total} submissions failed")));
+    }
+    if failed_tests > 0 {
+        return Err(anyhow::Error::from(CliError::TestsFailed).context(format!(
+            "Tests failed on the server for {failed_tests} of {total} submissions"
+        )));
+    }
+    Ok(())
+}
+
+/// Prints the awarded points of each exercise in a batch submission
+fn print_batch_submission_results(
+    io: &mut Io,
+    course_slug: &str,
+    submissions: &[BatchSubmission],
+) -> anyhow::Result<()> {
+    let json = submissions
+        .iter()
+        .map(|batch_submission| match &batch_submission.result {
+            Ok(result) => serde_json::json!({
+                "exercise": batch_submission.exercise_slug,
+                "submission": batch_submission.submission,
+                "result": result,
+            }),
+            Err(err) => serde_json::json!({
+                "exercise": batch_submission.exercise_slug,
+                "submission": batch_submission.submission,
+                "error": format!("{err:#}"),
+            }),
+        })
+        .collect::<Vec<_>>();
+    io.print_json(&json)?;
+
+    let width = submissions
+        .iter()
+        .map(|batch_submission| batch_submission.exercise_slug.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut total_points = 0;
+    io.println("", PrintColor::Normal)?;
+    io.println(
+        &format!("Submission results for course: {course_slug}"),
+        PrintColor::Normal,
+    )?;
+    for batch_submission in submissions {
+        io.print(
+            &format!("  {:width$}  ", batch_submission.exercise_slug),
+            PrintColor::Normal,
+        )?;
+        match &batch_submission.result {
+            Ok(result) => {
+                total_points += result.points.len();
+                let points = if result.points.is_empty() {
+                    "no new points awarded".to_string()
+                } else {
+                    format!("points awarded: [{}]", result.points.join(", "))
+                };
+                if result.all_tests_passed.unwrap_or_default() {
+                    io.println(&format!("All tests passed, {points}"), PrintColor::Success)?;
+                } else {
+                    let view_url = batch_submission
+                        .submission
+                        .as_ref()
+                        .map(|submission| format!(". See {}", submission.show_submission_url))
+                        .unwrap_or_default();
+                    io.println(
+                        &format!("Tests failed on server, {points}{view_url}"),
+                        PrintColor::Failed,
+                    )?;
+                }
+            }
+            Err(err) => {
+                io.println(&format!("Error: {err:#}"), PrintColor::Failed)?;
+            }
+        }
+    }
+    io.println("", PrintColor::Normal)?;
+    io.println(
+        &format!("Points permanently awarded in total: {total_points}"),
+        PrintColor::Normal,
+    )?;
+    Ok(())
+}
+
 fn print_wait_for_submission_results(
     io: &mut Io,
     submission_finished: SubmissionFinished,
@@ -152,3 +371,49 @@ fn into_locale(arg: &str) -> Result<Language> {
         .or_else(|| Language::from_639_3(arg))
         .with_context(|| format!("Invalid locale: {arg}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PLUGIN;
+
+    #[test]
+    fn batch_submission_outcome_test() {
+        let client = Client::new(
+            "http://localhost".parse().unwrap(),
+            PLUGIN.to_string(),
+            true,
+        )
+        .unwrap();
+        let finished = |all_tests_passed| {
+            let url = Url::parse("http://localhost/api/v8/core/submissions/1").unwrap();
+            let mut finished = client.wait_for_submission(url).unwrap();
+            finished.all_tests_passed = Some(all_tests_passed);
+            finished
+        };
+        let batch = |result| BatchSubmission {
+            exercise_slug: "part01-01_hello".to_string(),
+            submission: None,
+            result,
+        };
+
+        assert!(batch_submission_outcome(vec![batch(Ok(finished(true)))]).is_ok());
+
+        let err =
+            batch_submission_outcome(vec![batch(Ok(finished(true))), batch(Ok(finished(false)))])
+                .unwrap_err();
+        assert_eq!(error::exit_code(&err), 7);
+        assert!(format!("{err:#}").contains("Tests failed on the server for 1 of 2 submissions"));
+
+        let err = batch_submission_outcome(vec![
+            batch(Ok(finished(false))),
+            batch(Err(CliError::ServerError(
+                "Internal server error".to_string(),
+            )
+            .into())),
+        ])
+        .unwrap_err();
+        assert_eq!(error::exit_code(&err), 9);
+        assert!(format!("{err:#}").contains("1 of 2 submissions failed"));
+    }
+}
@@ -1,42 +1,305 @@
+mod report;
+
 use crate::{
+    cli::Report,
     commands::util,
     config::TmcCliConfig,
+    error::CliError,
     io::{Io, PrintColor},
 };
 use anyhow::Context;
-use std::{cmp::Ordering, collections::HashMap, path::Path};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
 use tmc_langs::{RunResult, RunStatus};
 
 /// Executes tmc tests for one exercise. If path not given, check if current folder is an exercise.
 /// If not, asks exercise with an interactive menu.
+///
+/// # Errors
+/// Returns `CliError::TestsFailed` or `CliError::CompileFailed` if the tests did not pass.
 #[tracing::instrument(skip_all)]
-pub fn test(io: &mut Io, path: Option<&str>, config: &TmcCliConfig) -> anyhow::Result<()> {
+pub fn test(
+    io: &mut Io,
+    path: Option<&str>,
+    config: &TmcCliConfig,
+    reports: &[Report],
+) -> anyhow::Result<()> {
     // todo: use context
     let exercise_path =
-        util::exercise_pathfinder(path, config).context("Error finding exercise")?;
-    test_exercise(io, &exercise_path, true)?;
-    Ok(())
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
+    let run_result = test_exercise(io, &exercise_path, true)?;
+    let outcome = match run_result.status {
+        RunStatus::Passed => Ok(()),
+        RunStatus::CompileFailed => Err(CliError::CompileFailed.into()),
+        RunStatus::TestsFailed | RunStatus::TestrunInterrupted | RunStatus::GenericError => {
+            Err(CliError::TestsFailed.into())
+        }
+    };
+    let exercise_name = util::exercise_name(&exercise_path);
+    report::write_reports(reports, &[(&exercise_name, Ok(run_result))])?;
+    outcome
 }
 
-/// Executes tests for a single exercise, returns true if all tests passed (false if not).
-fn test_exercise(io: &mut Io, path: &Path, print_progress: bool) -> anyhow::Result<bool> {
-    // Get exercise folder name from last component in path
-    let mut exercise_name = "";
-    for component in path.components() {
-        if let Some(component_string) = component.as_os_str().to_str() {
-            exercise_name = component_string;
+/// How often the exercise directory is checked for changes in watch mode
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the files need to stay unchanged before the tests are run again in watch mode
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Directories for build output directly under the exercise root, changes in them are ignored in watch mode.
+/// Deeper directories with these names, like a Java package called `build`, are watched.
+const WATCH_IGNORED_DIRS: &[&str] = &["target", "build", "bin", "obj", "out", "node_modules"];
+/// Cache directories that are ignored in watch mode wherever they are
+const WATCH_IGNORED_CACHE_DIRS: &[&str] = &["__pycache__"];
+
+/// Executes tmc tests for one exercise every time the files of the exercise change.
+/// The exercise is resolved the same way as in `test`. Runs until interrupted with Ctrl-C.
+#[tracing::instrument(skip_all)]
+pub fn watch(io: &mut Io, path: Option<&str>, config: &TmcCliConfig) -> anyhow::Result<()> {
+    let exercise_path =
+        util::exercise_pathfinder(io, path, config).context("Error finding exercise")?;
+    // output that is piped or redirected keeps the earlier runs instead of control sequences
+    let clear_screen = std::io::stdout().is_terminal();
+    loop {
+        if clear_screen {
+            io.clear_screen()?;
+        }
+        // the snapshot is taken before the run so that changes saved during the run trigger a new one
+        let snapshot = file_snapshot(&exercise_path)?;
+        if let Err(err) = test_exercise(io, &exercise_path, true) {
+            io.println(&format!("{err:#}"), PrintColor::Failed)?;
         }
+        io.println(
+            "\nWatching for changes, press Ctrl-C to exit...",
+            PrintColor::Normal,
+        )?;
+        wait_for_changes(&exercise_path, snapshot)?;
+    }
+}
+
+/// Blocks until the files in the directory differ from the snapshot
+/// and have then stayed unchanged for the debounce period
+fn wait_for_changes(dir: &Path, snapshot: BTreeMap<PathBuf, SystemTime>) -> anyhow::Result<()> {
+    loop {
+        thread::sleep(WATCH_POLL_INTERVAL);
+        let mut latest = file_snapshot(dir)?;
+        if latest != snapshot {
+            loop {
+                thread::sleep(WATCH_DEBOUNCE);
+                let current = file_snapshot(dir)?;
+                if current == latest {
+                    return Ok(());
+                }
+                latest = current;
+            }
+        }
+    }
+}
+
+/// Collects the modification times of the files in the directory, skipping hidden files,
+/// caches and build output directories
+fn file_snapshot(root: &Path) -> anyhow::Result<BTreeMap<PathBuf, SystemTime>> {
+    let mut snapshot = BTreeMap::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries =
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if file_name.starts_with('.') {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                let ignored = WATCH_IGNORED_CACHE_DIRS.contains(&file_name.as_ref())
+                    || (dir == root && WATCH_IGNORED_DIRS.contains(&file_name.as_ref()));
+                if !ignored {
+                    dirs.push(entry.path());
+                }
+            } else {
+                snapshot.insert(entry.path(), metadata.modified()?);
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Outcome of the tests of one exercise in a batch test run
+enum ExerciseOutcome {
+    Passed,
+    Failed,
+    CompileFailed,
+}
+
+struct ExerciseResult<'a> {
+    name: &'a str,
+    outcome: ExerciseOutcome,
+    /// Passed and total test counts, if the tests could be run
+    tests: Option<(usize, usize)>,
+}
+
+/// Executes tmc tests for every downloaded exercise of a course and prints a summary.
+/// If course not given, the course of the current directory is used.
+/// The results of all exercises are written into the same reports.
+///
+/// # Errors
+/// Returns `CliError::TestsFailed` if the tests of any exercise did not pass,
+/// or `CliError::CompileFailed` if the only failures were compilation failures.
+#[tracing::instrument(skip_all)]
+pub fn test_all(
+    io: &mut Io,
+    course: Option<&str>,
+    config: &TmcCliConfig,
+    reports: &[Report],
+) -> anyhow::Result<()> {
+    let (course_slug, exercise_dirs) =
+        util::course_exercise_dirs(course, config).context("Error finding course")?;
+    if exercise_dirs.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "No downloaded exercises found for course '{course_slug}'"
+        ))
+        .into());
+    }
+
+    let names = exercise_dirs
+        .iter()
+        .map(|exercise_dir| util::exercise_name(exercise_dir))
+        .collect::<Vec<_>>();
+    let mut results = Vec::new();
+    let mut run_results = Vec::new();
+    for (exercise_dir, name) in exercise_dirs.iter().zip(&names) {
+        let name = name.as_ref();
+        let result = match test_exercise(io, exercise_dir, false) {
+            Ok(run_result) => {
+                let passed = run_result
+                    .test_results
+                    .iter()
+                    .filter(|test_result| test_result.successful)
+                    .count();
+                let total = run_result.test_results.len();
+                let outcome = match run_result.status {
+                    RunStatus::Passed => ExerciseOutcome::Passed,
+                    RunStatus::CompileFailed => ExerciseOutcome::CompileFailed,
+                    RunStatus::TestsFailed
+                    | RunStatus::TestrunInterrupted
+                    | RunStatus::GenericError => ExerciseOutcome::Failed,
+                };
+                run_results.push((name, Ok(run_result)));
+                ExerciseResult {
+                    name,
+                    outcome,
+                    tests: Some((passed, total)),
+                }
+            }
+            Err(err) => {
+                io.println(
+                    &format!("Failed to run tests for {name}: {err:#}"),
+                    PrintColor::Failed,
+                )?;
+                run_results.push((name, Err(format!("{err:#}"))));
+                ExerciseResult {
+                    name,
+                    outcome: ExerciseOutcome::Failed,
+                    tests: None,
+                }
+            }
+        };
+        results.push(result);
     }
 
+    print_summary(io, &course_slug, &results)?;
+    report::write_reports(reports, &run_results)?;
+    if results
+        .iter()
+        .any(|result| matches!(result.outcome, ExerciseOutcome::Failed))
+    {
+        Err(CliError::TestsFailed.into())
+    } else if results
+        .iter()
+        .any(|result| matches!(result.outcome, ExerciseOutcome::CompileFailed))
+    {
+        Err(CliError::CompileFailed.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Prints a table of the outcomes of a batch test run
+fn print_summary(io: &mut Io, course_slug: &str, results: &[ExerciseResult]) -> anyhow::Result<()> {
+    let filter_names = |wanted: fn(&ExerciseOutcome) -> bool| {
+        results
+            .iter()
+            .filter(|result| wanted(&result.outcome))
+            .map(|result| result.name)
+            .collect::<Vec<_>>()
+    };
+    let passed = filter_names(|o| matches!(o, ExerciseOutcome::Passed));
+    let failed = filter_names(|o| matches!(o, ExerciseOutcome::Failed));
+    let compile_failed = filter_names(|o| matches!(o, ExerciseOutcome::CompileFailed));
+    io.print_json(&serde_json::json!({
+        "course": course_slug,
+        "passed": passed,
+        "failed": failed,
+        "compile_failed": compile_failed,
+    }))?;
+
+    let width = results
+        .iter()
+        .map(|result| result.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    io.println("", PrintColor::Normal)?;
+    io.println(&format!("Course: {course_slug}"), PrintColor::Normal)?;
+    for result in results {
+        let tests = result
+            .tests
+            .map(|(passed, total)| format!(" ({passed}/{total} tests passed)"))
+            .unwrap_or_default();
+        let (status, color) = match result.outcome {
+            ExerciseOutcome::Passed => ("Passed", PrintColor::Success),
+            ExerciseOutcome::Failed => ("Failed", PrintColor::Failed),
+            ExerciseOutcome::CompileFailed => ("Compilation failed", PrintColor::Failed),
+        };
+        io.print(&format!("  {:width$}  ", result.name), PrintColor::Normal)?;
+        io.println(&format!("{status}{tests}"), color)?;
+    }
+    io.println("", PrintColor::Normal)?;
+    io.println(
+        &format!(
+            "Passed: {}, failed: {}, compilation failed: {}",
+            passed.len(),
+            failed.len(),
+            compile_failed.len()
+        ),
+        PrintColor::Normal,
+    )?;
+    Ok(())
+}
+
+/// Executes tests for a single exercise and prints the results, returns the result of the test run.
+pub fn test_exercise(io: &mut Io, path: &Path, print_progress: bool) -> anyhow::Result<RunResult> {
+    let exercise_name = util::exercise_name(path);
+
     let run_result = tmc_langs::run_tests(path)?;
-    let res = print_result_test(io, run_result, exercise_name, print_progress)?;
-    Ok(res)
+    io.print_json(&serde_json::json!({
+        "exercise": exercise_name,
+        "result": run_result,
+    }))?;
+    print_result_test(io, &run_result, &exercise_name, print_progress)?;
+    Ok(run_result)
 }
 
 /// Prints the result of running tests for a single exercise
 fn print_result_test(
     io: &mut Io,
-    run_result: RunResult,
+    run_result: &RunResult,
     exercise_name: &str,
     print_progress: bool,
 ) -> anyhow::Result<bool> {
@@ -45,7 +308,7 @@ fn print_result_test(
 
     let mut tests_passed = 0;
     let mut tests_total = 0;
-    for test_result in run_result.test_results {
+    for test_result in &run_result.test_results {
         tests_total += 1;
         if test_result.successful {
             tests_passed += 1;
@@ -250,7 +513,8 @@ mod tests {
         let run_result = RunResult::new(RunStatus::Passed, vec![], logs);
         let exercise_name = "my_test_exercise";
 
-        let all_tests_passed = print_result_test(&mut io, run_result, exercise_name, true).unwrap();
+        let all_tests_passed =
+            print_result_test(&mut io, &run_result, exercise_name, true).unwrap();
 
         let output = String::from_utf8(output.into_inner()).unwrap();
         let output = output.lines().collect::<Vec<_>>();
@@ -287,7 +551,8 @@ mod tests {
         let run_result = RunResult::new(RunStatus::Passed, test_results, logs);
         let exercise_name = "my_test_exercise";
 
-        let all_tests_passed = print_result_test(&mut io, run_result, exercise_name, true).unwrap();
+        let all_tests_passed =
+            print_result_test(&mut io, &run_result, exercise_name, true).unwrap();
 
         let output = String::from_utf8(output.into_inner()).unwrap();
         let output = output.lines().collect::<Vec<_>>();
@@ -330,7 +595,8 @@ mod tests {
         let run_result = RunResult::new(RunStatus::TestsFailed, test_results, logs);
         let exercise_name = "my_test_exercise";
 
-        let all_tests_passed = print_result_test(&mut io, run_result, exercise_name, true).unwrap();
+        let all_tests_passed =
+            print_result_test(&mut io, &run_result, exercise_name, true).unwrap();
 
         let output = String::from_utf8(output.into_inner()).unwrap();
         let output = output.lines().collect::<Vec<_>>();
@@ -349,4 +615,74 @@ mod tests {
             "print_result_test returned true, expected false",
         );
     }
+
+    #[test]
+    fn print_summary_test() {
+        let mut output = NoColor::new(Vec::<u8>::new());
+        let mut input = Cursor::new(Vec::<u8>::new());
+        let mut io = Io::new(&mut output, &mut input);
+
+        let results = [
+            ExerciseResult {
+                name: "part01-01_passing",
+                outcome: ExerciseOutcome::Passed,
+                tests: Some((2, 2)),
+            },
+            ExerciseResult {
+                name: "part01-02_failing",
+                outcome: ExerciseOutcome::Failed,
+                tests: Some((1, 3)),
+            },
+            ExerciseResult {
+                name: "part01-03_broken",
+                outcome: ExerciseOutcome::CompileFailed,
+                tests: Some((0, 0)),
+            },
+        ];
+
+        print_summary(&mut io, "my_course", &results).unwrap();
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        let output = output.lines().collect::<Vec<_>>();
+        assert_eq!(output[0], "");
+        assert_contains(output[1], "my_course");
+        assert_contains(output[2], "part01-01_passing");
+        assert_contains(output[2], "Passed (2/2 tests passed)");
+        assert_contains(output[3], "part01-02_failing");
+        assert_contains(output[3], "Failed (1/3 tests passed)");
+        assert_contains(output[4], "part01-03_broken");
+        assert_contains(output[4], "Compilation failed");
+        assert_eq!(output[5], "");
+        assert_eq!(output[6], "Passed: 1, failed: 1, compilation failed: 1");
+    }
+
+    #[test]
+    fn file_snapshot_skips_build_output_test() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("src/build")).unwrap();
+        fs::create_dir_all(dir.path().join("src/__pycache__")).unwrap();
+        fs::create_dir_all(dir.path().join("target/classes")).unwrap();
+        fs::write(dir.path().join("src/Main.java"), "class Main {}").unwrap();
+        fs::write(
+            dir.path().join("src/build/Builder.java"),
+            "class Builder {}",
+        )
+        .unwrap();
+        fs::write(dir.path().join("src/__pycache__/main.pyc"), "").unwrap();
+        fs::write(dir.path().join("target/classes/Main.class"), "").unwrap();
+        fs::write(dir.path().join(".tmc_test_results.json"), "{}").unwrap();
+
+        let snapshot = file_snapshot(dir.path()).unwrap();
+
+        // build output is only skipped directly under the exercise root
+        let files = snapshot.keys().collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            [
+                &dir.path().join("src/Main.java"),
+                &dir.path().join("src/build/Builder.java"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,373 @@
+//! Serializes test results into JUnit XML and TAP reports
+
+use crate::cli::{Report, ReportFormat};
+use anyhow::Context;
+use std::{collections::HashMap, io::Write};
+use tmc_langs::{RunResult, RunStatus, TestResult};
+
+/// The test results of an exercise, or the error that kept its tests from being run
+pub type ExerciseRun<'a> = (&'a str, Result<RunResult, String>);
+
+/// Writes the test results of the exercises into every requested report.
+/// Reports without a file are written to stdout.
+pub fn write_reports(reports: &[Report], results: &[ExerciseRun]) -> anyhow::Result<()> {
+    for report in reports {
+        let contents = match report.format {
+            ReportFormat::Junit => junit(results),
+            ReportFormat::Tap => tap(results),
+        };
+        match &report.path {
+            Some(path) => std::fs::write(path, contents)
+                .with_context(|| format!("Failed to write report to {}", path.display()))?,
+            None => std::io::stdout().write_all(contents.as_bytes())?,
+        }
+    }
+    Ok(())
+}
+
+/// Describes a run that ended before the test results were available, None for normal runs
+fn run_error(status: &RunStatus) -> Option<&'static str> {
+    match status {
+        RunStatus::Passed | RunStatus::TestsFailed => None,
+        RunStatus::CompileFailed => Some("Compilation failed"),
+        RunStatus::TestrunInterrupted => Some("The test run was interrupted"),
+        RunStatus::GenericError => Some("An unexpected error occurred"),
+    }
+}
+
+/// The parts of the test run of an exercise that the reports are made of
+struct Run<'a> {
+    status: String,
+    passed: bool,
+    test_results: &'a [TestResult],
+    logs: Option<&'a HashMap<String, String>>,
+    /// Name and message of the error that kept the tests from running normally
+    error: Option<(&'static str, String)>,
+}
+
+impl<'a> Run<'a> {
+    fn new(run: &'a Result<RunResult, String>) -> Self {
+        match run {
+            Ok(run_result) => Self {
+                status: format!("{:?}", run_result.status),
+                passed: matches!(run_result.status, RunStatus::Passed),
+                test_results: &run_result.test_results,
+                logs: Some(&run_result.logs),
+                error: run_error(&run_result.status).map(|error| (error, error.to_string())),
+            },
+            Err(message) => Self {
+                status: "Error".to_string(),
+                passed: false,
+                test_results: &[],
+                logs: None,
+                error: Some(("The tests could not be run", message.clone())),
+            },
+        }
+    }
+}
+
+/// Serializes the results into a JUnit XML document with one testsuite per exercise
+pub fn junit(results: &[ExerciseRun]) -> String {
+    let mut suites = String::new();
+    let (mut total_tests, mut total_failures, mut total_errors) = (0, 0, 0);
+    for (exercise_name, run) in results {
+        let run = Run::new(run);
+        let exercise_name = xml_attr(exercise_name);
+        let tests = run.test_results.len();
+        let failures = run
+            .test_results
+            .iter()
+            .filter(|test_result| !test_result.successful)
+            .count();
+        let errors = usize::from(run.error.is_some());
+
+        suites.push_str(&format!(
+            "  <testsuite name=\"{exercise_name}\" tests=\"{}\" failures=\"{failures}\" \
+            errors=\"{errors}\">\n",
+            tests + errors
+        ));
+        suites.push_str(&format!(
+            "    <properties><property name=\"status\" value=\"{}\"/></properties>\n",
+            run.status
+        ));
+        for test_result in run.test_results {
+            suites.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{exercise_name}\">\n",
+                xml_attr(&test_result.name)
+            ));
+            if !test_result.points.is_empty() {
+                suites.push_str(&format!(
+                    "      <properties><property name=\"points\" value=\"{}\"/></properties>\n",
+                    xml_attr(&test_result.points.join(" "))
+                ));
+            }
+            if !test_result.successful {
+                suites.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    xml_attr(&test_result.message),
+                    xml_escape(&test_result.exception.join("\n"))
+                ));
+            }
+            suites.push_str("    </testcase>\n");
+        }
+        if let Some((name, message)) = &run.error {
+            suites.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"{exercise_name}\">\n"
+            ));
+            suites.push_str(&format!(
+                "      <error message=\"{}\"/>\n",
+                xml_attr(message)
+            ));
+            suites.push_str("    </testcase>\n");
+        }
+        for (log, element) in [("stdout", "system-out"), ("stderr", "system-err")] {
+            if let Some(log) = run
+                .logs
+                .and_then(|logs| logs.get(log))
+                .filter(|log| !log.is_empty())
+            {
+                suites.push_str(&format!("    <{element}>{}</{element}>\n", xml_escape(log)));
+            }
+        }
+        suites.push_str("  </testsuite>\n");
+
+        total_tests += tests + errors;
+        total_failures += failures;
+        total_errors += errors;
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <testsuites tests=\"{total_tests}\" failures=\"{total_failures}\" errors=\"{total_errors}\">\n\
+        {suites}\
+        </testsuites>\n"
+    )
+}
+
+/// Serializes the results into a TAP version 14 document with one subtest per exercise
+pub fn tap(results: &[ExerciseRun]) -> String {
+    let mut tap = String::from("TAP version 14\n");
+    tap.push_str(&format!("1..{}\n", results.len()));
+    for (i, (exercise_name, run)) in results.iter().enumerate() {
+        let run = Run::new(run);
+        tap.push_str(&format!("# Subtest: {exercise_name}\n"));
+        tap.push_str(&format!("    1..{}\n", run.test_results.len()));
+        for (j, test_result) in run.test_results.iter().enumerate() {
+            let ok = if test_result.successful {
+                "ok"
+            } else {
+                "not ok"
+            };
+            tap.push_str(&format!(
+                "    {ok} {} - {}\n",
+                j + 1,
+                tap_escape(&test_result.name)
+            ));
+            if !test_result.successful || !test_result.points.is_empty() {
+                tap.push_str("      ---\n");
+                if !test_result.successful {
+                    tap.push_str(&format!(
+                        "      message: {}\n",
+                        yaml_string(&test_result.message)
+                    ));
+                }
+                if !test_result.points.is_empty() {
+                    tap.push_str(&format!(
+                        "      points: {}\n",
+                        yaml_list(&test_result.points)
+                    ));
+                }
+                if !test_result.exception.is_empty() {
+                    tap.push_str(&format!(
+                        "      exception: {}\n",
+                        yaml_list(&test_result.exception)
+                    ));
+                }
+                tap.push_str("      ...\n");
+            }
+        }
+
+        let ok = if run.passed { "ok" } else { "not ok" };
+        tap.push_str(&format!("{ok} {} - {}\n", i + 1, tap_escape(exercise_name)));
+        if !run.passed {
+            tap.push_str("  ---\n");
+            tap.push_str(&format!("  status: {}\n", run.status));
+            if let Some((_, message)) = &run.error {
+                tap.push_str(&format!("  message: {}\n", yaml_string(message)));
+            }
+            let mut logs = run
+                .logs
+                .into_iter()
+                .flatten()
+                .filter(|(_, log)| !log.is_empty())
+                .collect::<Vec<_>>();
+            logs.sort();
+            if !logs.is_empty() {
+                tap.push_str("  logs:\n");
+                for (key, log) in logs {
+                    tap.push_str(&format!("    {key}: {}\n", yaml_string(log)));
+                }
+            }
+            tap.push_str("  ...\n");
+        }
+    }
+    tap
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            // other control characters are not allowed in XML 1.0
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Line breaks in attribute values are normalized to spaces unless they are escaped
+fn xml_attr(text: &str) -> String {
+    xml_escape(text).replace('\n', "&#10;")
+}
+
+/// Test descriptions may not contain '#' (starts a directive) or line breaks
+fn tap_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('#', "\\#")
+        .replace('\n', " ")
+}
+
+/// JSON strings are valid YAML flow scalars and escape everything that needs escaping
+fn yaml_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+fn yaml_list(items: &[String]) -> String {
+    serde_json::Value::from(items).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<ExerciseRun<'static>> {
+        let passed = TestResult {
+            name: "first_test".to_string(),
+            successful: true,
+            points: vec!["1.1".to_string()],
+            message: "".to_string(),
+            exception: vec![],
+        };
+        let failed = TestResult {
+            name: "second_test".to_string(),
+            successful: false,
+            points: vec![],
+            message: "expected <1> but was <2>".to_string(),
+            exception: vec!["at Test.java:10".to_string()],
+        };
+        let logs = HashMap::from([("stdout".to_string(), "hello".to_string())]);
+        vec![
+            (
+                "part01-01_exercise",
+                Ok(RunResult::new(
+                    RunStatus::TestsFailed,
+                    vec![passed, failed],
+                    logs,
+                )),
+            ),
+            (
+                "part01-02_broken",
+                Ok(RunResult::new(
+                    RunStatus::CompileFailed,
+                    vec![],
+                    HashMap::new(),
+                )),
+            ),
+            (
+                "part01-03_unknown",
+                Err("No matching language plugin found".to_string()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn junit_report_test() {
+        let report = junit(&results());
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="4" failures="1" errors="2">
+  <testsuite name="part01-01_exercise" tests="2" failures="1" errors="0">
+    <properties><property name="status" value="TestsFailed"/></properties>
+    <testcase name="first_test" classname="part01-01_exercise">
+      <properties><property name="points" value="1.1"/></properties>
+    </testcase>
+    <testcase name="second_test" classname="part01-01_exercise">
+      <failure message="expected &lt;1&gt; but was &lt;2&gt;">at Test.java:10</failure>
+    </testcase>
+    <system-out>hello</system-out>
+  </testsuite>
+  <testsuite name="part01-02_broken" tests="1" failures="0" errors="1">
+    <properties><property name="status" value="CompileFailed"/></properties>
+    <testcase name="Compilation failed" classname="part01-02_broken">
+      <error message="Compilation failed"/>
+    </testcase>
+  </testsuite>
+  <testsuite name="part01-03_unknown" tests="1" failures="0" errors="1">
+    <properties><property name="status" value="Error"/></properties>
+    <testcase name="The tests could not be run" classname="part01-03_unknown">
+      <error message="No matching language plugin found"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+        assert_eq!(report, expected);
+    }
+
+    #[test]
+    fn tap_report_test() {
+        let report = tap(&results());
+
+        let expected = r#"TAP version 14
+1..3
+# Subtest: part01-01_exercise
+    1..2
+    ok 1 - first_test
+      ---
+      points: ["1.1"]
+      ...
+    not ok 2 - second_test
+      ---
+      message: "expected <1> but was <2>"
+      exception: ["at Test.java:10"]
+      ...
+not ok 1 - part01-01_exercise
+  ---
+  status: TestsFailed
+  logs:
+    stdout: "hello"
+  ...
+# Subtest: part01-02_broken
+    1..0
+not ok 2 - part01-02_broken
+  ---
+  status: CompileFailed
+  message: "Compilation failed"
+  ...
+# Subtest: part01-03_unknown
+    1..0
+not ok 3 - part01-03_unknown
+  ---
+  status: Error
+  message: "No matching language plugin found"
+  ...
+"#;
+        assert_eq!(report, expected);
+    }
+}
@@ -1,3 +1,4 @@
+use super::download;
 use crate::{
     client::Client,
     config::TmcCliConfig,
@@ -36,7 +37,7 @@ pub fn update(
         config.get_projects_dir().to_path_buf()
     };
     let tmp_path = path.to_str().context("invalid path")?;
-    match call_update(&path, client) {
+    match call_update(io, &path, client) {
         Ok(msg) => io.println(&format!("\n{msg}"), PrintColor::Success)?,
         Err(msg) => {
             let os = std::env::consts::OS;
@@ -73,8 +74,13 @@ pub fn update(
     Ok(())
 }
 
-fn call_update(path: &Path, client: &mut Client) -> anyhow::Result<String> {
-    client.update_exercises(path)?;
+fn call_update(io: &mut Io, path: &Path, client: &mut Client) -> anyhow::Result<String> {
+    let result = client.update_exercises(path)?;
+    io.print_json(&download::download_summary(
+        &result.downloaded,
+        &result.skipped,
+        result.failed.as_deref().unwrap_or_default(),
+    ))?;
     Ok(format!(
         "Exercises updated succesfully to {}",
         path.to_str().context("invalid path")?
@@ -95,7 +101,7 @@ pub fn elevated_update(
     std::fs::remove_file(temp_file_path)?;
     let path = PathBuf::from(params);
     io.println("", PrintColor::Normal)?;
-    let msg = call_update(&path, client)?;
+    let msg = call_update(io, &path, client)?;
     io.println(&msg, PrintColor::Success)?;
     pause()?;
     Ok(())
@@ -1,27 +1,211 @@
+use super::exercises;
 use crate::{
+    cache::{self, Cache, CacheMode},
     client::Client,
     config::TmcCliConfig,
+    error::CliError,
     interactive::{self, interactive_list},
     io::{Io, PrintColor},
-    PLUGIN,
+    progress_reporting,
 };
 use anyhow::Context;
-use std::{env, path::PathBuf};
-use tmc_langs::{tmc::response::Course, Credentials, ProjectsConfig};
+use chrono::{DateTime, FixedOffset, Local};
+use std::{
+    borrow::Cow,
+    env,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+use tmc_langs::{
+    tmc::response::{Course, CourseDetails, CourseExercise},
+    Credentials, ProjectsConfig,
+};
+
+/// How many requests are sent to the server at the same time when fetching course details
+const COURSE_DETAILS_WORKERS: usize = 8;
 
-pub fn get_credentials() -> Option<Credentials> {
+pub fn get_credentials(credentials_name: &str) -> Option<Credentials> {
     // Load login credentials if they exist in the file
-    Credentials::load(PLUGIN).unwrap_or(None)
+    Credentials::load(credentials_name).unwrap_or(None)
 }
 
-/// Returns course as: Ok(Some(Course)) or Ok(None) if not found, Err(msg) if could not get courses list
-pub fn get_course_by_name(
-    client: &mut Client,
-    course_name: &str,
-    org: &str,
-) -> anyhow::Result<Option<Course>> {
+/// How many similarly named courses are suggested when no course matches
+const MAX_COURSE_SUGGESTIONS: usize = 3;
+
+/// Finds the course of the organization that the user referred to by its name, id or title,
+/// ignoring case.
+///
+/// # Errors
+/// Returns `CliError::NotFound` with the most similar courses as suggestions if no course matches,
+/// and an error listing the candidates if several courses match.
+pub fn find_course(client: &mut Client, query: &str, org: &str) -> anyhow::Result<Course> {
     let courses = client.list_courses(org)?;
-    Ok(courses.into_iter().find(|c| c.name == course_name))
+    resolve_course(courses, query, "course")
+}
+
+/// Finds the downloaded course that the user referred to by its name, ignoring case,
+/// or by its id or title when the course is found in `courses`. Returns the name of the course.
+///
+/// # Errors
+/// Returns `CliError::NotFound` with the most similar courses as suggestions if no course matches,
+/// and an error listing the candidates if several courses match.
+pub fn find_downloaded_course<'a>(
+    projects_config: &'a ProjectsConfig,
+    courses: &'a [Course],
+    query: &str,
+) -> anyhow::Result<&'a str> {
+    let downloaded = projects_config
+        .courses
+        .keys()
+        .map(|name| DownloadedCourse {
+            name,
+            course: courses.iter().find(|course| &course.name == name),
+        })
+        .collect();
+    let found = resolve_course(downloaded, query, "downloaded course")?;
+    Ok(found.name)
+}
+
+/// The courses from the server that are in the projects directory are known by their name
+/// until the details of the course have been fetched
+struct DownloadedCourse<'a> {
+    name: &'a str,
+    course: Option<&'a Course>,
+}
+
+/// The ways a user can refer to a course
+trait CourseKey {
+    fn id(&self) -> Option<u32>;
+    fn name(&self) -> &str;
+    fn title(&self) -> &str;
+}
+
+impl CourseKey for Course {
+    fn id(&self) -> Option<u32> {
+        Some(self.id)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+}
+
+impl CourseKey for DownloadedCourse<'_> {
+    fn id(&self) -> Option<u32> {
+        self.course.map(|course| course.id)
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn title(&self) -> &str {
+        self.course.map_or("", |course| course.title.as_str())
+    }
+}
+
+/// Turns the match into the course or a `CliError::NotFound` describing the `kind` of course
+fn resolve_course<T: CourseKey>(courses: Vec<T>, query: &str, kind: &str) -> anyhow::Result<T> {
+    match match_course(courses, query) {
+        CourseMatch::Found(course) => Ok(course),
+        CourseMatch::Ambiguous(candidates) => {
+            let candidates = candidates
+                .iter()
+                .map(|course| format!("\n  {}", describe_course(course)))
+                .collect::<String>();
+            anyhow::bail!(
+                "'{query}' matches several courses, use the name of the course instead:{candidates}"
+            )
+        }
+        CourseMatch::NotFound(suggestions) => {
+            let mut message = format!("Could not find a {kind} matching '{query}'.");
+            if !suggestions.is_empty() {
+                let suggestions = suggestions
+                    .iter()
+                    .map(|course| format!("\n  {}", describe_course(course)))
+                    .collect::<String>();
+                message.push_str(&format!(" Did you mean:{suggestions}"));
+            }
+            Err(CliError::NotFound(message).into())
+        }
+    }
+}
+
+#[derive(Debug)]
+enum CourseMatch<T> {
+    Found(T),
+    Ambiguous(Vec<T>),
+    /// Contains the most similar courses
+    NotFound(Vec<T>),
+}
+
+/// An exact match of the name wins, after that the name, id and title are compared ignoring case
+fn match_course<T: CourseKey>(mut courses: Vec<T>, query: &str) -> CourseMatch<T> {
+    let query = query.trim();
+    if let Some(index) = courses.iter().position(|course| course.name() == query) {
+        return CourseMatch::Found(courses.swap_remove(index));
+    }
+
+    let lowercase_query = query.to_lowercase();
+    let id = query.parse::<u32>().ok();
+    let (mut matches, others): (Vec<_>, Vec<_>) = courses.into_iter().partition(|course| {
+        id.is_some() && course.id() == id
+            || course.name().to_lowercase() == lowercase_query
+            || course.title().to_lowercase() == lowercase_query
+    });
+    match matches.len() {
+        0 => {}
+        1 => return CourseMatch::Found(matches.remove(0)),
+        _ => return CourseMatch::Ambiguous(matches),
+    }
+
+    // allow roughly one typo for every three characters
+    let max_distance = (lowercase_query.chars().count() / 3).max(1);
+    let mut suggestions = others
+        .into_iter()
+        .filter_map(|course| {
+            let distance = edit_distance(&course.name().to_lowercase(), &lowercase_query).min(
+                edit_distance(&course.title().to_lowercase(), &lowercase_query),
+            );
+            (distance <= max_distance).then_some((distance, course))
+        })
+        .collect::<Vec<_>>();
+    suggestions.sort_by_key(|(distance, _)| *distance);
+    CourseMatch::NotFound(
+        suggestions
+            .into_iter()
+            .take(MAX_COURSE_SUGGESTIONS)
+            .map(|(_, course)| course)
+            .collect(),
+    )
+}
+
+fn describe_course(course: &impl CourseKey) -> String {
+    if course.title().is_empty() {
+        course.name().to_string()
+    } else {
+        format!("{} ({})", course.name(), course.title())
+    }
+}
+
+/// The Levenshtein distance between the strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 /// Finds an exercise
@@ -32,7 +216,11 @@ pub fn get_course_by_name(
 ///
 /// # Errors
 /// Returns an error if the last chance, interactive menu, fails.
-pub fn exercise_pathfinder(path: Option<&str>, config: &TmcCliConfig) -> anyhow::Result<PathBuf> {
+pub fn exercise_pathfinder(
+    io: &mut Io,
+    path: Option<&str>,
+    config: &TmcCliConfig,
+) -> anyhow::Result<PathBuf> {
     // check if parameter was given
     if let Some(ex_path) = path {
         let buf = PathBuf::from(ex_path);
@@ -53,37 +241,88 @@ pub fn exercise_pathfinder(path: Option<&str>, config: &TmcCliConfig) -> anyhow:
                 if is_ex_path {
                     Ok(ex_path)
                 } else {
-                    choose_exercise(config)
+                    choose_exercise(io, config)
                 }
             }
-            Err(_err) => choose_exercise(config),
+            Err(_err) => choose_exercise(io, config),
         },
-        None => choose_exercise(config),
+        None => choose_exercise(io, config),
     }
 }
 
+/// Finds a downloaded course and returns its slug with the paths of its exercises
+/// Priority to check for the course:
+/// 1. Checks optional parameter from the projects directory by name, id or title
+/// 2. Checks if current directory is an exercise directory or a course directory
+///
+/// # Errors
+/// Returns an error if no downloaded course was found.
+pub fn course_exercise_dirs(
+    course: Option<&str>,
+    config: &TmcCliConfig,
+) -> anyhow::Result<(String, Vec<PathBuf>)> {
+    let (projects_dir, course_slug) = if let Some(course) = course {
+        (config.get_projects_dir().to_path_buf(), course.to_string())
+    } else {
+        let current_dir = env::current_dir()?;
+        if is_exercise_dir(current_dir.clone()).unwrap_or_default() {
+            let (projects_dir, course_slug, _exercise_slug) = parse_exercise_dir(current_dir)?;
+            (projects_dir, course_slug)
+        } else {
+            let course_slug = current_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .context("No course given and the current directory is not a course directory")?
+                .to_string();
+            let projects_dir = current_dir
+                .parent()
+                .context("No course given and the current directory is not a course directory")?
+                .to_path_buf();
+            (projects_dir, course_slug)
+        }
+    };
+
+    let projects_config = ProjectsConfig::load(&projects_dir).with_context(|| {
+        format!(
+            "Failed to load projects config from directory '{}'",
+            projects_dir.display(),
+        )
+    })?;
+    let course_slug = if course.is_some() {
+        find_downloaded_course(&projects_config, &cached_courses(config)?, &course_slug)?
+            .to_string()
+    } else {
+        course_slug
+    };
+    let course_config = projects_config.courses.get(&course_slug).with_context(|| {
+        format!(
+            "No downloaded course '{course_slug}' found. Project directory set to {}",
+            projects_dir.display()
+        )
+    })?;
+    let course_dir = projects_dir.join(&course_slug);
+    let exercise_dirs = course_config
+        .exercises
+        .keys()
+        .map(|exercise| course_dir.join(exercise))
+        .collect();
+    Ok((course_slug, exercise_dirs))
+}
+
 pub fn choose_course(io: &mut Io, client: &mut Client, org: &str) -> anyhow::Result<String> {
     io.println("Fetching courses...", PrintColor::Normal)?;
     let courses = client
         .list_courses(org)
         .context("Could not list courses.")?;
 
-    let mut courses = courses
-        .iter()
-        .map(|course| client.get_course_details(course.id))
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut courses = get_courses_details(io, client, &courses)?;
     courses.sort_by(|a, b| {
         a.course
             .title
             .to_lowercase()
             .cmp(&b.course.title.to_lowercase())
     });
-    let course = get_course_name(
-        &courses
-            .iter()
-            .map(|course| course.course.title.as_str())
-            .collect::<Vec<_>>(),
-    )?;
+    let course = get_course_name(io, &courses, &[])?;
     let selection = courses
         .into_iter()
         .find(|c| c.course.title == course)
@@ -93,9 +332,108 @@ pub fn choose_course(io: &mut Io, client: &mut Client, org: &str) -> anyhow::Res
     Ok(selection)
 }
 
-pub fn get_course_name(courses: &[&str]) -> anyhow::Result<String> {
-    let course = interactive::interactive_list("Select your course:", courses)?
-        .ok_or_else(|| anyhow::anyhow!("Didn't select any course"))?;
+/// Fetches the details of the courses concurrently while showing a spinner.
+/// Courses whose details could not be fetched are left out with a warning.
+pub fn get_courses_details(
+    io: &mut Io,
+    client: &Client,
+    courses: &[Course],
+) -> anyhow::Result<Vec<CourseDetails>> {
+    let spinner = progress_reporting::spinner("Fetching course details...", client.test_mode);
+    let (details, failed) = map_concurrently(courses, COURSE_DETAILS_WORKERS, |course| {
+        let details = client.get_course_details(course.id);
+        spinner.tick();
+        details
+    });
+    spinner.finish_and_clear();
+
+    for (course, err) in &failed {
+        log::warn!(
+            "Failed to fetch the details of course {}: {err:#}",
+            course.name
+        );
+    }
+    if !failed.is_empty() {
+        let names = failed
+            .iter()
+            .map(|(course, _)| course.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        io.println(
+            &format!("Skipped courses whose details could not be fetched: {names}"),
+            PrintColor::Warning,
+        )?;
+    }
+    Ok(details)
+}
+
+/// Calls f for every item using at most the given number of threads.
+/// Returns the successful results in the order of the items and the items that failed with their errors.
+fn map_concurrently<T, R, F>(
+    items: &[T],
+    workers: usize,
+    f: F,
+) -> (Vec<R>, Vec<(&T, anyhow::Error)>)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> anyhow::Result<R> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let handles = (0..workers.min(items.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        results.push((index, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("the worker should not panic"))
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut successes = Vec::new();
+    let mut failures = Vec::new();
+    for (index, result) in results {
+        match result {
+            Ok(value) => successes.push(value),
+            Err(err) => failures.push((&items[index], err)),
+        }
+    }
+    (successes, failures)
+}
+
+/// Asks the user to select a course by its title while showing the details of the highlighted course.
+/// Pinned items are shown regardless of the filter.
+pub fn get_course_name(
+    io: &mut Io,
+    courses: &[CourseDetails],
+    pinned: &[&str],
+) -> anyhow::Result<String> {
+    let titles = courses
+        .iter()
+        .map(|course| course.course.title.as_str())
+        .collect::<Vec<_>>();
+    let details = |index: usize| describe_course_details(&courses[index]);
+    let course = interactive::interactive_list_with_pinned(
+        io,
+        "Select your course:",
+        &titles,
+        pinned,
+        Some(&details),
+    )?
+    .ok_or_else(|| CliError::Cancelled("Didn't select any course".to_string()))?;
 
     if course.is_empty() {
         anyhow::bail!("Could not find a course by the given title");
@@ -104,9 +442,53 @@ pub fn get_course_name(courses: &[&str]) -> anyhow::Result<String> {
     }
 }
 
+/// The title, name, description and exercise count of a course for the details pane of the course menu
+fn describe_course_details(course: &CourseDetails) -> String {
+    let mut details = format!(
+        "{}\nName: {}\nExercises: {}",
+        course.course.title,
+        course.course.name,
+        course.exercises.len()
+    );
+    push_description(&mut details, course.course.description.as_deref());
+    details
+}
+
+/// The title, description and downloaded exercise count of a course
+/// for the details pane of the menu of downloaded courses
+fn describe_downloaded_course(name: &str, course: Option<&Course>, downloaded: usize) -> String {
+    match course {
+        Some(course) => {
+            let mut details = format!(
+                "{}\nName: {name}\nDownloaded exercises: {downloaded}",
+                course.title
+            );
+            push_description(&mut details, course.description.as_deref());
+            details
+        }
+        None => format!("{name}\nDownloaded exercises: {downloaded}"),
+    }
+}
+
+fn push_description(details: &mut String, description: Option<&str>) {
+    if let Some(description) = description.filter(|description| !description.trim().is_empty()) {
+        details.push_str(&format!("\n\n{}", description.trim()));
+    }
+}
+
 /// Choose course and then exercise interactively, return exercise path
 /// or Err(String) if either menu is interrupted or no items found
-pub fn choose_exercise(config: &TmcCliConfig) -> anyhow::Result<PathBuf> {
+/// The courses of the selected organization as listed by earlier commands,
+/// so no connection is needed
+fn cached_courses(config: &TmcCliConfig) -> anyhow::Result<Vec<Course>> {
+    let cache = Cache::new(config.cache_dir()?, CacheMode::Offline);
+    Ok(config
+        .get_organization()
+        .and_then(|org| cache.peek::<Vec<Course>>(&cache::courses_key(org)))
+        .unwrap_or_default())
+}
+
+pub fn choose_exercise(io: &mut Io, config: &TmcCliConfig) -> anyhow::Result<PathBuf> {
     let mut courses = Vec::new();
 
     let projects_config = match ProjectsConfig::load(config.get_projects_dir()) {
@@ -129,8 +511,19 @@ pub fn choose_exercise(config: &TmcCliConfig) -> anyhow::Result<PathBuf> {
     }
 
     courses.sort();
-    let chosen_course = interactive_list("First select course: ", &courses)?
-        .ok_or_else(|| anyhow::anyhow!("Didn't select any course"))?;
+    let cache = Cache::new(config.cache_dir()?, CacheMode::Offline);
+    let cached_courses = cached_courses(config)?;
+    let cached_course = |name: &str| cached_courses.iter().find(|course| course.name == name);
+    let course_details = |index: usize| {
+        let downloaded = projects_config
+            .courses
+            .get(courses[index])
+            .map_or(0, |course_config| course_config.exercises.len());
+        describe_downloaded_course(courses[index], cached_course(courses[index]), downloaded)
+    };
+    let chosen_course =
+        interactive_list(io, "First select course: ", &courses, Some(&course_details))?
+            .ok_or_else(|| CliError::Cancelled("Didn't select any course".to_string()))?;
 
     let course_config = projects_config
         .courses
@@ -153,8 +546,30 @@ pub fn choose_exercise(config: &TmcCliConfig) -> anyhow::Result<PathBuf> {
         );
     }
 
-    let chosen_exercise = interactive_list("Select exercise: ", &exercise_list)?
-        .ok_or_else(|| anyhow::anyhow!("Didn't select any exercise"))?;
+    let cached_exercises = cached_course(&chosen_course)
+        .and_then(|course| {
+            cache.peek::<Vec<CourseExercise>>(&cache::course_exercises_key(course.id))
+        })
+        .unwrap_or_default();
+    let exercise_details = |index: usize| {
+        let name = exercise_list[index];
+        match cached_exercises
+            .iter()
+            .find(|exercise| exercise.name == name)
+        {
+            Some(exercise) => exercises::describe_exercise(exercise),
+            None => format!(
+                "{name}\nNo details cached, run `tmc exercises {chosen_course}` to fetch them"
+            ),
+        }
+    };
+    let chosen_exercise = interactive_list(
+        io,
+        "Select exercise: ",
+        &exercise_list,
+        Some(&exercise_details),
+    )?
+    .ok_or_else(|| CliError::Cancelled("Didn't select any exercise".to_string()))?;
 
     let mut path = config.get_projects_dir().to_path_buf();
     path.push(chosen_course);
@@ -188,6 +603,36 @@ pub fn parse_exercise_dir(mut exercise_dir: PathBuf) -> anyhow::Result<(PathBuf,
     Ok((exercise_dir, course_slug, exercise_slug))
 }
 
+/// Returns the name of an exercise, the last component of its path
+pub fn exercise_name(exercise_dir: &Path) -> Cow<'_, str> {
+    exercise_dir
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_else(|| exercise_dir.to_string_lossy())
+}
+
+/// Finds the id of a downloaded exercise from the projects config
+///
+/// # Errors
+/// Returns `CliError::NotFound` if the exercise is not in the projects config
+pub fn exercise_id(
+    projects_config: &ProjectsConfig,
+    course_slug: &str,
+    exercise_slug: &str,
+) -> anyhow::Result<u32> {
+    projects_config
+        .courses
+        .get(course_slug)
+        .and_then(|course_config| course_config.exercises.get(exercise_slug))
+        .map(|exercise| exercise.id)
+        .ok_or_else(|| {
+            CliError::NotFound(format!(
+                "Exercise {exercise_slug} not found in course {course_slug}"
+            ))
+            .into()
+        })
+}
+
 /// Checks if provided directory contains an exercise
 ///
 /// # Errors
@@ -205,6 +650,23 @@ pub fn is_exercise_dir(dir: PathBuf) -> anyhow::Result<bool> {
     Ok(config.courses.contains_key(&course_slug))
 }
 
+/// Parses a deadline given by the server, returns None if it is not a valid RFC 3339 timestamp
+pub fn parse_deadline(deadline: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(deadline).ok()
+}
+
+/// Formats a deadline from the server in local time, or leaves it as is if it can't be parsed
+pub fn format_deadline(deadline: &str) -> String {
+    parse_deadline(deadline)
+        .map(|deadline| {
+            deadline
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| deadline.to_string())
+}
+
 /// Returns a manual progress bar of size 'length' based on percentage of 'completed' / 'total'
 pub fn get_progress_string(completed: usize, total: usize, length: usize) -> String {
     let completed_proportion = if total == 0 {
@@ -232,3 +694,150 @@ pub fn get_progress_string(completed: usize, total: usize, length: usize) -> Str
     };
     format!("{spaces}{completed_percentage_readable}%[{progress_string}]")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn course(id: u32, name: &str, title: &str) -> Course {
+        Course {
+            id,
+            name: name.to_string(),
+            title: title.to_string(),
+            description: None,
+            details_url: "".to_string(),
+            unlock_url: "".to_string(),
+            reviews_url: "".to_string(),
+            comet_url: "".to_string(),
+            spyware_urls: vec![],
+        }
+    }
+
+    fn names(courses: &[Course]) -> Vec<&str> {
+        courses.iter().map(|course| course.name.as_str()).collect()
+    }
+
+    #[test]
+    fn match_course_test() {
+        let courses = vec![
+            course(1, "java-programming-1", "Java Programming I"),
+            course(2, "java-programming-2", "Java Programming II"),
+            course(3, "python-2024", "Python Programming"),
+            course(4, "python-2023", "Python Programming"),
+        ];
+        let find = |query| match match_course(courses.clone(), query) {
+            CourseMatch::Found(course) => course.name,
+            other => panic!("expected a match for {query}, got {other:?}"),
+        };
+
+        assert_eq!(find("java-programming-2"), "java-programming-2");
+        assert_eq!(find("JAVA-PROGRAMMING-2"), "java-programming-2");
+        assert_eq!(find("3"), "python-2024");
+        assert_eq!(find("java programming i"), "java-programming-1");
+
+        match match_course(courses.clone(), "Python Programming") {
+            CourseMatch::Ambiguous(candidates) => {
+                assert_eq!(names(&candidates), ["python-2024", "python-2023"])
+            }
+            other => panic!("expected an ambiguous match, got {other:?}"),
+        }
+        match match_course(courses.clone(), "pyhton-2024") {
+            CourseMatch::NotFound(suggestions) => {
+                assert_eq!(names(&suggestions), ["python-2024", "python-2023"])
+            }
+            other => panic!("expected no match, got {other:?}"),
+        }
+        match match_course(courses, "haskell") {
+            CourseMatch::NotFound(suggestions) => assert!(suggestions.is_empty()),
+            other => panic!("expected no match, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn match_downloaded_course_test() {
+        let python = course(3, "python-2024", "Python Programming");
+        let downloaded = || {
+            vec![
+                DownloadedCourse {
+                    name: "java-programming-1",
+                    course: None,
+                },
+                DownloadedCourse {
+                    name: "python-2024",
+                    course: Some(&python),
+                },
+            ]
+        };
+        let find = |query| resolve_course(downloaded(), query, "downloaded course").map(|c| c.name);
+
+        assert_eq!(find("Java-Programming-1").unwrap(), "java-programming-1");
+        assert_eq!(find("3").unwrap(), "python-2024");
+        assert_eq!(find("python programming").unwrap(), "python-2024");
+        let err = find("python-2025").unwrap_err();
+        assert_eq!(crate::error::exit_code(&err), 6);
+        assert!(err
+            .to_string()
+            .contains("Did you mean:\n  python-2024 (Python Programming)"));
+    }
+
+    #[test]
+    fn describe_downloaded_course_test() {
+        let mut cached = course(3, "python-2024", "Python Programming");
+        cached.description = Some("Learn Python ".to_string());
+        assert_eq!(
+            describe_downloaded_course("python-2024", Some(&cached), 2),
+            "Python Programming\nName: python-2024\nDownloaded exercises: 2\n\nLearn Python"
+        );
+        assert_eq!(
+            describe_downloaded_course("python-2024", None, 2),
+            "python-2024\nDownloaded exercises: 2"
+        );
+    }
+
+    #[test]
+    fn exercise_name_test() {
+        let path = Path::new("tmc").join("test-course").join("part01-01_hello");
+        assert_eq!(exercise_name(&path), "part01-01_hello");
+        assert_eq!(
+            exercise_name(Path::new("part01-01_hello")),
+            "part01-01_hello"
+        );
+    }
+
+    #[test]
+    fn edit_distance_test() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("pyhton", "python"), 2);
+        assert_eq!(edit_distance("äö", "ao"), 2);
+    }
+
+    #[test]
+    fn map_concurrently_test() {
+        let items = (0..20).collect::<Vec<u32>>();
+        let (successes, failures) = map_concurrently(&items, 4, |item| {
+            if item % 7 == 3 {
+                anyhow::bail!("failed {item}");
+            }
+            Ok(item * 2)
+        });
+
+        let expected = items
+            .iter()
+            .filter(|item| *item % 7 != 3)
+            .map(|item| item * 2)
+            .collect::<Vec<_>>();
+        assert_eq!(successes, expected);
+        let failed = failures.iter().map(|(item, _)| **item).collect::<Vec<_>>();
+        assert_eq!(failed, [3, 10, 17]);
+        assert_eq!(failures[0].1.to_string(), "failed 3");
+    }
+
+    #[test]
+    fn map_concurrently_without_items_test() {
+        let (successes, failures) = map_concurrently(&[] as &[u32], 4, |item| anyhow::Ok(*item));
+        assert!(successes.is_empty());
+        assert!(failures.is_empty());
+    }
+}
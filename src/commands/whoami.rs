@@ -0,0 +1,103 @@
+use crate::{
+    client::{Client, User},
+    config::{TmcCliConfig, DEFAULT_PROFILE},
+    error::CliError,
+    io::{Io, PrintColor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenStatus {
+    Valid,
+    Invalid,
+    Missing,
+}
+
+impl TokenStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Invalid => "invalid, login again with 'tmc login'",
+            Self::Missing => "not logged in",
+        }
+    }
+}
+
+/// Shows who the user is logged in as, the server, organization and directories in use
+/// and whether the stored token is still accepted by the server.
+///
+/// # Errors
+/// Returns `CliError::NotLoggedIn` if there is no login
+/// and `CliError::InvalidToken` if the token is no longer accepted.
+pub fn whoami(io: &mut Io, client: &mut Client, config: &TmcCliConfig) -> anyhow::Result<()> {
+    let (user, token_status) = if client.load_login(config).is_ok() {
+        match client.get_current_user()? {
+            Some(user) => (Some(user), TokenStatus::Valid),
+            None => (None, TokenStatus::Invalid),
+        }
+    } else {
+        (None, TokenStatus::Missing)
+    };
+
+    let config_path = TmcCliConfig::location()?;
+    io.print_json(&serde_json::json!({
+        "user": user,
+        "token": match token_status {
+            TokenStatus::Valid => "valid",
+            TokenStatus::Invalid => "invalid",
+            TokenStatus::Missing => "missing",
+        },
+        "root_url": client.root_url(),
+        "profile": config.selected_profile(),
+        "organization": config.get_organization(),
+        "config_path": config_path,
+        "projects_dir": config.get_projects_dir(),
+    }))?;
+
+    let rows = [
+        (
+            "Logged in as",
+            user.as_ref()
+                .map(format_user)
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("Server", client.root_url().to_string()),
+        (
+            "Profile",
+            config
+                .selected_profile()
+                .unwrap_or(DEFAULT_PROFILE)
+                .to_string(),
+        ),
+        (
+            "Organization",
+            config.get_organization().unwrap_or("-").to_string(),
+        ),
+        ("Config file", config_path.display().to_string()),
+        (
+            "Projects directory",
+            config.get_projects_dir().display().to_string(),
+        ),
+    ];
+    for (label, value) in rows {
+        io.println(
+            &format!("{:<20}{value}", format!("{label}:")),
+            PrintColor::Normal,
+        )?;
+    }
+    let color = match token_status {
+        TokenStatus::Valid => PrintColor::Success,
+        TokenStatus::Invalid | TokenStatus::Missing => PrintColor::Failed,
+    };
+    io.print(&format!("{:<20}", "Token:"), PrintColor::Normal)?;
+    io.println(token_status.label(), color)?;
+
+    match token_status {
+        TokenStatus::Valid => Ok(()),
+        TokenStatus::Invalid => Err(CliError::InvalidToken.into()),
+        TokenStatus::Missing => Err(CliError::NotLoggedIn.into()),
+    }
+}
+
+fn format_user(user: &User) -> String {
+    format!("{} ({})", user.username, user.email)
+}
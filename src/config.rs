@@ -1,15 +1,62 @@
 //! Wrapper around TmcConfig
 
 use crate::PLUGIN;
-use std::path::{Path, PathBuf};
+use anyhow::Context;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 use tmc_langs::TmcConfig;
 
 const ORGANIZATION_KEY: &str = "organization";
 const TEST_LOGIN_KEY: &str = "test_login";
 const TEST_LOGIN_VALUE: &str = "test_logged_in";
+const PROFILES_KEY: &str = "profiles";
+const ACTIVE_PROFILE_KEY: &str = "profile";
+const ROOT_URL_KEY: &str = "root_url";
+const CACHE_DIR: &str = "cache";
+
+/// Name that refers to the built-in tmc.mooc.fi setup instead of a saved profile
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A named TMC server with its own organization and credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub root_url: String,
+    pub organization: Option<String>,
+}
+
+impl Profile {
+    fn from_toml(value: &toml::Value) -> Option<Self> {
+        Some(Self {
+            root_url: value.get(ROOT_URL_KEY)?.as_str()?.to_string(),
+            organization: value
+                .get(ORGANIZATION_KEY)
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        })
+    }
+
+    fn to_toml(&self) -> toml::Value {
+        let mut table = toml::value::Table::new();
+        table.insert(
+            ROOT_URL_KEY.to_string(),
+            toml::Value::String(self.root_url.clone()),
+        );
+        if let Some(organization) = &self.organization {
+            table.insert(
+                ORGANIZATION_KEY.to_string(),
+                toml::Value::String(organization.clone()),
+            );
+        }
+        toml::Value::Table(table)
+    }
+}
 
 pub struct TmcCliConfig {
     config: TmcConfig,
+    /// The profile used for this run, None for the default setup
+    profile: Option<String>,
 }
 
 impl TmcCliConfig {
@@ -20,11 +67,14 @@ impl TmcCliConfig {
 
     pub fn load(path: PathBuf) -> anyhow::Result<Self> {
         let config = TmcConfig::load_from(PLUGIN, path)?;
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            profile: None,
+        })
     }
 
     pub fn save(&mut self) -> anyhow::Result<()> {
-        let Self { config } = self;
+        let Self { config, .. } = self;
 
         config.save()?;
 
@@ -36,12 +86,147 @@ impl TmcCliConfig {
     }
 
     pub fn get_organization(&self) -> Option<&str> {
-        self.config.get(ORGANIZATION_KEY).and_then(|v| v.as_str())
+        match &self.profile {
+            Some(profile) => self
+                .config
+                .get(PROFILES_KEY)
+                .and_then(|profiles| profiles.get(profile))
+                .and_then(|profile| profile.get(ORGANIZATION_KEY))
+                .and_then(|v| v.as_str()),
+            None => self.config.get(ORGANIZATION_KEY).and_then(|v| v.as_str()),
+        }
     }
 
     pub fn set_organization(&mut self, org: String) {
+        match self.profile.clone() {
+            Some(name) => {
+                if let Some(mut profile) = self.get_profile(&name) {
+                    profile.organization = Some(org);
+                    self.set_profile(name, &profile);
+                }
+            }
+            None => {
+                self.config
+                    .insert(ORGANIZATION_KEY.to_string(), toml::Value::String(org));
+            }
+        }
+    }
+
+    pub fn get_profiles(&self) -> BTreeMap<String, Profile> {
+        self.config
+            .get(PROFILES_KEY)
+            .and_then(|v| v.as_table())
+            .map(|profiles| {
+                profiles
+                    .iter()
+                    .filter_map(|(name, profile)| {
+                        Some((name.clone(), Profile::from_toml(profile)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_profile(&self, name: &str) -> Option<Profile> {
+        self.config
+            .get(PROFILES_KEY)
+            .and_then(|profiles| profiles.get(name))
+            .and_then(Profile::from_toml)
+    }
+
+    pub fn set_profile(&mut self, name: String, profile: &Profile) {
+        let mut profiles = self
+            .config
+            .get(PROFILES_KEY)
+            .and_then(|v| v.as_table())
+            .cloned()
+            .unwrap_or_default();
+        profiles.insert(name, profile.to_toml());
         self.config
-            .insert(ORGANIZATION_KEY.to_string(), toml::Value::String(org));
+            .insert(PROFILES_KEY.to_string(), toml::Value::Table(profiles));
+    }
+
+    /// Removes a saved profile, returns false if it did not exist
+    pub fn remove_profile(&mut self, name: &str) -> bool {
+        let Some(mut profiles) = self
+            .config
+            .get(PROFILES_KEY)
+            .and_then(|v| v.as_table())
+            .cloned()
+        else {
+            return false;
+        };
+        let removed = profiles.remove(name).is_some();
+        self.config
+            .insert(PROFILES_KEY.to_string(), toml::Value::Table(profiles));
+        if self.get_active_profile() == Some(name) {
+            self.set_active_profile(None);
+        }
+        removed
+    }
+
+    /// The profile that is used when --profile is not given
+    pub fn get_active_profile(&self) -> Option<&str> {
+        self.config.get(ACTIVE_PROFILE_KEY).and_then(|v| v.as_str())
+    }
+
+    pub fn set_active_profile(&mut self, name: Option<String>) {
+        match name {
+            Some(name) => {
+                self.config
+                    .insert(ACTIVE_PROFILE_KEY.to_string(), toml::Value::String(name));
+            }
+            None => {
+                self.config.remove(ACTIVE_PROFILE_KEY);
+            }
+        }
+    }
+
+    /// Selects the profile for this run, falling back to the active profile if name is None
+    ///
+    /// # Errors
+    /// Returns an error if the profile does not exist.
+    pub fn select_profile(&mut self, name: Option<&str>) -> anyhow::Result<()> {
+        let name = name
+            .or_else(|| self.get_active_profile())
+            .filter(|name| *name != DEFAULT_PROFILE)
+            .map(str::to_string);
+        if let Some(name) = &name {
+            self.get_profile(name).with_context(|| {
+                format!("No profile named '{name}'. You can add one with 'tmc profile add'")
+            })?;
+        }
+        self.profile = name;
+        Ok(())
+    }
+
+    /// The profile used for this run, None for the default setup
+    pub fn selected_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// The root URL of the selected profile
+    pub fn get_root_url(&self) -> Option<String> {
+        self.profile
+            .as_deref()
+            .and_then(|name| self.get_profile(name))
+            .map(|profile| profile.root_url)
+    }
+
+    /// Name under which the credentials of the selected profile are stored
+    pub fn credentials_name(&self) -> String {
+        credentials_name(self.profile.as_deref())
+    }
+
+    /// Directory of the cached server listings of the selected profile
+    pub fn cache_dir(&self) -> anyhow::Result<PathBuf> {
+        let location = Self::location()?;
+        let config_dir = location
+            .parent()
+            .context("The config file should be inside a directory")?;
+        Ok(config_dir
+            .join(CACHE_DIR)
+            .join(self.profile.as_deref().unwrap_or(DEFAULT_PROFILE)))
     }
 
     pub fn get_test_login(&self) -> Option<&str> {
@@ -59,6 +244,14 @@ impl TmcCliConfig {
     }
 }
 
+/// Name under which the credentials of a profile are stored, so that each server has its own login
+pub fn credentials_name(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{PLUGIN}-{profile}"),
+        None => PLUGIN.to_string(),
+    }
+}
+
 #[cfg(target_os = "windows")]
 impl TmcCliConfig {
     const UPDATE_LAST_CHECKED_KEY: &'static str = "update-last-checked";
@@ -82,3 +275,41 @@ impl TmcCliConfig {
             .insert(key, toml::Value::String(timestamp.to_string()));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn profile_organization_test() {
+        let config_file = NamedTempFile::new().unwrap();
+        let mut config = TmcCliConfig::load(config_file.path().to_path_buf()).unwrap();
+        config.set_organization("mooc".to_string());
+        config.set_profile(
+            "internal".to_string(),
+            &Profile {
+                root_url: "https://tmc.example.com".to_string(),
+                organization: None,
+            },
+        );
+
+        config.select_profile(Some("internal")).unwrap();
+        assert_eq!(config.get_organization(), None);
+        config.set_organization("internal-org".to_string());
+        assert_eq!(config.get_organization(), Some("internal-org"));
+        assert_eq!(
+            config.get_root_url().as_deref(),
+            Some("https://tmc.example.com")
+        );
+        assert_eq!(config.credentials_name(), "tmc_cli_rust-internal");
+
+        config.select_profile(None).unwrap();
+        assert_eq!(config.get_organization(), Some("mooc"));
+        assert_eq!(config.credentials_name(), "tmc_cli_rust");
+
+        assert!(config.select_profile(Some("missing")).is_err());
+        assert!(config.remove_profile("internal"));
+        assert!(config.get_profiles().is_empty());
+    }
+}
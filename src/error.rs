@@ -0,0 +1,170 @@
+//! Error kinds that decide the exit code of the process
+
+use std::fmt;
+use tmc_langs::{tmc::TestMyCodeClientError, LangsError};
+
+pub const WRONG_LOGIN: &str = "Wrong username or password";
+pub const INVALID_TOKEN: &str = "Login token is invalid. Please try logging in again.";
+
+/// A failure that scripts may want to react to. Each kind has its own exit code,
+/// other errors exit with `GENERAL_EXIT_CODE`.
+#[derive(Debug)]
+pub enum CliError {
+    /// No stored login was found
+    NotLoggedIn,
+    /// The server did not accept the stored token
+    InvalidToken,
+    /// The username or password was wrong
+    WrongLogin,
+    /// The server could not be reached
+    Network(String),
+    /// A course, exercise or other resource does not exist
+    NotFound(String),
+    /// The tests of an exercise did not pass
+    TestsFailed,
+    /// An exercise could not be compiled
+    CompileFailed,
+    /// The server responded with an error
+    ServerError(String),
+    /// The user cancelled the command
+    Cancelled(String),
+}
+
+/// Exit code for errors that are not a `CliError`
+pub const GENERAL_EXIT_CODE: u8 = 1;
+
+impl CliError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotLoggedIn => 3,
+            Self::InvalidToken | Self::WrongLogin => 4,
+            Self::Network(_) => 5,
+            Self::NotFound(_) => 6,
+            Self::TestsFailed => 7,
+            Self::CompileFailed => 8,
+            Self::ServerError(_) => 9,
+            Self::Cancelled(_) => 10,
+        }
+    }
+
+    /// Converts an error from the TMC server client, keeping the kind of the failure
+    pub fn from_client_error(err: TestMyCodeClientError) -> anyhow::Error {
+        match Self::classify_client_error(&err) {
+            Some(cli_error) => cli_error.into(),
+            None => anyhow::anyhow!("Unexpected error: '{err}'."),
+        }
+    }
+
+    /// Converts an error from the TMC server client like `from_client_error`,
+    /// treating failures of an unknown kind as server errors
+    pub fn from_server_error(err: TestMyCodeClientError) -> anyhow::Error {
+        Self::classify_client_error(&err)
+            .unwrap_or_else(|| Self::ServerError(err.to_string()))
+            .into()
+    }
+
+    fn classify_client_error(err: &TestMyCodeClientError) -> Option<Self> {
+        match err {
+            TestMyCodeClientError::NotAuthenticated => Some(Self::InvalidToken),
+            TestMyCodeClientError::HttpError { status, .. } => match status.as_u16() {
+                401 | 403 => Some(Self::InvalidToken),
+                404 => Some(Self::NotFound(err.to_string())),
+                _ => Some(Self::ServerError(err.to_string())),
+            },
+            _ if is_network_error(err) => Some(Self::Network(err.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotLoggedIn => {
+                write!(
+                    f,
+                    "No login found. Login to use this command with 'tmc login'"
+                )
+            }
+            Self::InvalidToken => write!(f, "{INVALID_TOKEN}"),
+            Self::WrongLogin => write!(f, "{WRONG_LOGIN}"),
+            Self::Network(message) => write!(f, "Could not connect to the server: {message}"),
+            Self::NotFound(message) | Self::ServerError(message) | Self::Cancelled(message) => {
+                write!(f, "{message}")
+            }
+            Self::TestsFailed => write!(f, "Not all tests passed"),
+            Self::CompileFailed => write!(f, "Compilation failed"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Converts an error from tmc-langs, keeping the kind of the failure if it came from the server client
+pub fn from_langs_error(err: LangsError) -> anyhow::Error {
+    match err {
+        LangsError::TestMyCodeClient(err) => CliError::from_client_error(err),
+        err => anyhow::Error::new(err),
+    }
+}
+
+/// Finds the exit code for an error by looking for a known kind of failure in its chain of causes
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(cli_error) = cause.downcast_ref::<CliError>() {
+            return cli_error.exit_code();
+        }
+        if let Some(cli_error) = cause
+            .downcast_ref::<TestMyCodeClientError>()
+            .and_then(CliError::classify_client_error)
+        {
+            return cli_error.exit_code();
+        }
+        if is_network_error(cause) {
+            return CliError::Network(cause.to_string()).exit_code();
+        }
+    }
+    GENERAL_EXIT_CODE
+}
+
+/// Checks whether the server could not be reached at all
+pub fn is_network_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut cause = Some(err);
+    while let Some(err) = cause {
+        if err
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| err.is_connect() || err.is_timeout())
+        {
+            return true;
+        }
+        cause = err.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn exit_code_test() {
+        let err = anyhow::Error::from(CliError::TestsFailed);
+        assert_eq!(exit_code(&err), 7);
+
+        let err = Err::<(), _>(CliError::NotLoggedIn)
+            .context("Could not download")
+            .unwrap_err();
+        assert_eq!(exit_code(&err), 3);
+
+        let err = Err::<(), _>(CliError::from_client_error(
+            TestMyCodeClientError::NotAuthenticated,
+        ))
+        .context("Could not list courses")
+        .unwrap_err();
+        assert_eq!(exit_code(&err), 4);
+
+        let err = anyhow::anyhow!("Something else went wrong");
+        assert_eq!(exit_code(&err), GENERAL_EXIT_CODE);
+    }
+}
@@ -1,3 +1,5 @@
+mod fuzzy;
+mod numbered;
 pub mod prompt;
 pub mod state;
 
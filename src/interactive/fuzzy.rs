@@ -0,0 +1,84 @@
+/// Score of every matched character
+const MATCH_SCORE: i64 = 16;
+/// Extra score for a character that directly follows the previous matched character
+const CONSECUTIVE_BONUS: i64 = 16;
+/// Extra score for a character that starts a word, e.g. "m" in "Ohjelmoinnin MOOC"
+const WORD_START_BONUS: i64 = 12;
+/// Penalty for every skipped character between the first and the last match
+const GAP_PENALTY: i64 = 1;
+/// Penalty for every character before the first match
+const LEADING_PENALTY: i64 = 2;
+
+/// How well a filter matched an item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Indices of the matched characters in the item, counted in chars
+    pub indices: Vec<usize>,
+}
+
+/// Matches the characters of the pattern in order anywhere in the item, ignoring case,
+/// so that "ohjm21" matches "Ohjelmoinnin MOOC 2021".
+/// Returns None if the item does not contain all of the characters in order.
+pub fn fuzzy_match(pattern: &str, item: &str) -> Option<FuzzyMatch> {
+    let chars = item.chars().collect::<Vec<_>>();
+    let mut indices = Vec::new();
+    let mut score = 0;
+    let mut position = 0;
+
+    for pattern_char in pattern.chars() {
+        let index = (position..chars.len()).find(|&i| eq_ignore_case(chars[i], pattern_char))?;
+
+        score += MATCH_SCORE;
+        match indices.last() {
+            Some(&previous) if previous + 1 == index => score += CONSECUTIVE_BONUS,
+            Some(&previous) => score -= GAP_PENALTY * (index - previous - 1) as i64,
+            None => score -= LEADING_PENALTY * index as i64,
+        }
+        if is_word_start(&chars, index) {
+            score += WORD_START_BONUS;
+        }
+
+        indices.push(index);
+        position = index + 1;
+    }
+    Some(FuzzyMatch { score, indices })
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    let current = chars[index];
+    !previous.is_alphanumeric()
+        || (previous.is_lowercase() && current.is_uppercase())
+        || (previous.is_alphabetic() && current.is_numeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_match_test() {
+        let found = fuzzy_match("ohjm21", "Ohjelmoinnin MOOC 2021").unwrap();
+        assert_eq!(found.indices, [0, 1, 2, 5, 18, 21]);
+        assert!(fuzzy_match("ohjm21", "Ohjelmoinnin MOOC 2020").is_none());
+        assert!(fuzzy_match("cba", "abc").is_none());
+        assert_eq!(fuzzy_match("", "abc").unwrap().indices, Vec::<usize>::new());
+        assert_eq!(fuzzy_match("ÄÖ", "häiriö").unwrap().indices, [1, 5]);
+    }
+
+    #[test]
+    fn fuzzy_match_score_test() {
+        let score = |pattern, item| fuzzy_match(pattern, item).unwrap().score;
+
+        // earlier, consecutive and word starting matches are preferred
+        assert!(score("py", "Python Programming") > score("py", "Data Analysis with Python"));
+        assert!(score("java", "Java Programming") > score("java", "J a v a"));
+    }
+}
@@ -0,0 +1,209 @@
+//! Plain-text selection for when the interactive prompt can't be used,
+//! e.g. when the input or output is piped, in CI or in a dumb terminal
+
+use super::fuzzy::fuzzy_match;
+use crate::io::{Io, PrintColor};
+
+/// Prints the items as a numbered list and reads the choice from the input.
+/// The choice can be a number from the list, or text that filters the list
+/// the same way as typing in the interactive prompt. A filter that matches
+/// exactly one item, or an item exactly, selects it. An exact item match
+/// is preferred over reading the input as numbers.
+/// In a multi-select list, several numbers and ranges like `1 3 5-7` or `all` can be given.
+///
+/// Returns the indices of the chosen items, or None if the input was empty or ended.
+pub fn numbered_list(
+    io: &mut Io,
+    prompt: &str,
+    items: &[&str],
+    multi_select: bool,
+) -> anyhow::Result<Option<Vec<usize>>> {
+    let all = (0..items.len()).collect::<Vec<_>>();
+    let mut displayed = all.clone();
+
+    loop {
+        io.println(prompt, PrintColor::Normal)?;
+        let width = displayed.len().to_string().len();
+        for (number, &index) in displayed.iter().enumerate() {
+            io.println(
+                &format!("  {:>width$}) {}", number + 1, items[index]),
+                PrintColor::Normal,
+            )?;
+        }
+        let hint = if multi_select {
+            "Enter numbers (e.g. 1 3 5-7), all, or text to filter the list. An empty line cancels: "
+        } else {
+            "Enter a number or text to filter the list. An empty line cancels: "
+        };
+        io.print(hint, PrintColor::Normal)?;
+
+        let input = io.read_line()?;
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        // an item named like a number, e.g. "2024", is chosen by its name
+        if let Some(index) = items
+            .iter()
+            .position(|item| item.eq_ignore_ascii_case(input))
+        {
+            return Ok(Some(vec![index]));
+        }
+
+        if let Some(numbers) = parse_numbers(input, displayed.len(), multi_select) {
+            match numbers {
+                Ok(numbers) => {
+                    return Ok(Some(
+                        numbers
+                            .into_iter()
+                            .map(|number| displayed[number])
+                            .collect(),
+                    ))
+                }
+                Err(message) => {
+                    io.println(&message, PrintColor::Warning)?;
+                    continue;
+                }
+            }
+        }
+
+        let mut matches = all
+            .iter()
+            .filter_map(|&index| fuzzy_match(input, items[index]).map(|found| (index, found)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(_, found)| std::cmp::Reverse(found.score));
+        match matches.as_slice() {
+            [] => io.println(&format!("No items match '{input}'"), PrintColor::Warning)?,
+            [(index, _)] if !multi_select => return Ok(Some(vec![*index])),
+            _ => displayed = matches.into_iter().map(|(index, _)| index).collect(),
+        }
+    }
+}
+
+/// Parses the input as numbers of the displayed items, starting from 1.
+/// Returns None if the input is not a list of numbers,
+/// and an error message if the numbers are not valid for the list.
+fn parse_numbers(
+    input: &str,
+    count: usize,
+    multi_select: bool,
+) -> Option<Result<Vec<usize>, String>> {
+    if multi_select && input.eq_ignore_ascii_case("all") {
+        return Some(Ok((0..count).collect()));
+    }
+
+    let mut ranges = Vec::new();
+    for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        let start = start.trim().parse::<usize>().ok()?;
+        let end = end.trim().parse::<usize>().ok()?;
+        ranges.push((start, end));
+    }
+
+    if !multi_select && (ranges.len() > 1 || ranges.iter().any(|(start, end)| start != end)) {
+        return Some(Err("Select only one item".to_string()));
+    }
+    let mut numbers = Vec::new();
+    for (start, end) in ranges {
+        if start == 0 || end > count || start > end {
+            return Some(Err(format!(
+                "Not a valid choice: {input}. Enter numbers from 1 to {count}"
+            )));
+        }
+        for number in start - 1..end {
+            if !numbers.contains(&number) {
+                numbers.push(number);
+            }
+        }
+    }
+    Some(Ok(numbers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use termcolor::NoColor;
+
+    const ITEMS: &[&str] = &[
+        "Ohjelmoinnin MOOC 2020",
+        "Ohjelmoinnin MOOC 2021",
+        "Python Programming MOOC",
+        "View all organizations",
+    ];
+
+    fn select(input: &str, multi_select: bool) -> (Option<Vec<usize>>, String) {
+        select_from(ITEMS, input, multi_select)
+    }
+
+    fn select_from(
+        items: &[&str],
+        input: &str,
+        multi_select: bool,
+    ) -> (Option<Vec<usize>>, String) {
+        let mut input = Cursor::new(input.as_bytes().to_vec());
+        let mut output = NoColor::new(Vec::<u8>::new());
+        let selected = {
+            let mut io = Io::new(&mut output, &mut input);
+            numbered_list(&mut io, "Select:", items, multi_select).unwrap()
+        };
+        (selected, String::from_utf8(output.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn numbered_list_test() {
+        let (selected, output) = select("2\n", false);
+        assert_eq!(selected, Some(vec![1]));
+        assert!(output.contains("Select:\n  1) Ohjelmoinnin MOOC 2020\n"));
+        assert!(output.contains("  4) View all organizations\n"));
+
+        // an empty line or the end of input cancels
+        assert_eq!(select("\n", false).0, None);
+        assert_eq!(select("", false).0, None);
+
+        // invalid numbers are asked again
+        let (selected, output) = select("5\n1-2\n3\n", false);
+        assert_eq!(selected, Some(vec![2]));
+        assert!(output.contains("Enter numbers from 1 to 4"));
+        assert!(output.contains("Select only one item"));
+    }
+
+    #[test]
+    fn numbered_list_filter_test() {
+        // a single match is selected directly
+        assert_eq!(select("python\n", false).0, Some(vec![2]));
+        assert_eq!(select("view all organizations\n", false).0, Some(vec![3]));
+
+        // the numbers refer to the filtered list
+        let (selected, output) = select("ohj\n2\n", false);
+        assert_eq!(selected, Some(vec![1]));
+        assert!(output.contains("  2) Ohjelmoinnin MOOC 2021\n"));
+
+        let (selected, output) = select("java\nmooc 2021\n", false);
+        assert_eq!(selected, Some(vec![1]));
+        assert!(output.contains("No items match 'java'"));
+    }
+
+    #[test]
+    fn numbered_list_number_label_test() {
+        let items = ["2023", "2024", "1"];
+        // exact labels are chosen by name before being read as numbers
+        assert_eq!(select_from(&items, "2024\n", false).0, Some(vec![1]));
+        assert_eq!(select_from(&items, "1\n", false).0, Some(vec![2]));
+        assert_eq!(select_from(&items, "2\n", false).0, Some(vec![1]));
+        assert_eq!(select_from(&items, "2023\n", true).0, Some(vec![0]));
+    }
+
+    #[test]
+    fn numbered_multi_select_test() {
+        assert_eq!(select("1 3-4\n", true).0, Some(vec![0, 2, 3]));
+        assert_eq!(select("2,1,2\n", true).0, Some(vec![1, 0]));
+        assert_eq!(select("all\n", true).0, Some(vec![0, 1, 2, 3]));
+        assert_eq!(select("ohj\nall\n", true).0, Some(vec![0, 1]));
+        assert_eq!(select("3-1\n", true).0, None);
+    }
+}
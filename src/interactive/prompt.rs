@@ -1,4 +1,8 @@
-use super::state::AppState;
+use super::{
+    numbered::numbered_list,
+    state::{AppState, Details},
+};
+use crate::io::Io;
 use crossterm::{
     event::{poll, read, Event, KeyCode, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -7,12 +11,16 @@ use crossterm::{
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Terminal,
 };
-use std::{io::stdout, time::Duration};
+use std::{
+    env,
+    io::{stdin, stdout, IsTerminal},
+    time::Duration,
+};
 
 /// control the maximum waiting time for event availability
 /// in this case, the value should not really matter,
@@ -29,13 +37,90 @@ const POLL_RATE: u64 = 1000;
 /// let items = vec![String::from("Eka"), String::from("Toka"), String::from("Kolmas"),
 /// String::from("Neljäs")];
 ///
-/// let choice = interactive_list(prompt, items);
+/// let choice = interactive_list(io, prompt, items, None);
 ///
 /// if let Some(choice) = choice {
 ///     println!("You chose: {}", choice);
 /// }
 /// ```
-pub fn interactive_list(prompt: &str, items: &[&str]) -> anyhow::Result<Option<String>> {
+///
+/// if details are given, the details of the highlighted item are shown next to the list
+///
+/// if stdin or stdout is not a terminal, the items are listed with numbers through `io` instead
+/// and the choice is read from the input, see `numbered_list`
+pub fn interactive_list(
+    io: &mut Io,
+    prompt: &str,
+    items: &[&str],
+    details: Option<Details>,
+) -> anyhow::Result<Option<String>> {
+    interactive_list_with_pinned(io, prompt, items, &[], details)
+}
+
+/// display an interactive prompt like `interactive_list`,
+/// with pinned items that are always displayed after the items that match the filter
+pub fn interactive_list_with_pinned(
+    io: &mut Io,
+    prompt: &str,
+    items: &[&str],
+    pinned: &[&str],
+    details: Option<Details>,
+) -> anyhow::Result<Option<String>> {
+    if !is_terminal() {
+        let items = items.iter().chain(pinned).copied().collect::<Vec<_>>();
+        let selected = numbered_list(io, prompt, &items, false)?;
+        return Ok(selected.and_then(|selected| selected.first().map(|&i| items[i].to_string())));
+    }
+    let mut app = AppState::new(items)
+        .with_pinned(pinned)
+        .with_details(details);
+    run_prompt(&mut app, prompt, |app| app.get_selected())
+}
+
+/// display an interactive prompt to ask the user to select any number of items
+/// space checks or unchecks an item and a, tab or CTRL-A all of the displayed items,
+/// a only while the filter is empty, every other letter goes to the filter
+///
+/// returns the indices of the checked items,
+/// or the index of the highlighted item if nothing was checked
+pub fn interactive_multi_select(
+    io: &mut Io,
+    prompt: &str,
+    items: &[&str],
+    details: Option<Details>,
+) -> anyhow::Result<Option<Vec<usize>>> {
+    if !is_terminal() {
+        return numbered_list(io, prompt, items, true);
+    }
+    let mut app = AppState::new(items).multi_select().with_details(details);
+    run_prompt(&mut app, prompt, |app| {
+        if app.checked.is_empty() {
+            app.items
+                .state
+                .selected()
+                .and_then(|selected| app.origins.get(selected).copied().flatten())
+                .map(|index| vec![index])
+        } else {
+            Some(app.checked.iter().copied().collect())
+        }
+    })
+}
+
+/// whether the interactive prompt can be drawn,
+/// which needs both stdin and stdout to be a terminal that is not dumb
+fn is_terminal() -> bool {
+    stdin().is_terminal()
+        && stdout().is_terminal()
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// runs the prompt in the alternate screen until the user either cancels
+/// or confirms with a selection returned by `selection`
+fn run_prompt<T>(
+    app: &mut AppState,
+    prompt: &str,
+    selection: impl Fn(&AppState) -> Option<T>,
+) -> anyhow::Result<Option<T>> {
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
@@ -44,8 +129,7 @@ pub fn interactive_list(prompt: &str, items: &[&str]) -> anyhow::Result<Option<S
     stdout().execute(EnterAlternateScreen)?;
     terminal.clear()?;
 
-    let mut app = AppState::new(items);
-    let result = event_loop(&mut terminal, &mut app, prompt)?;
+    let result = event_loop(&mut terminal, app, prompt, selection)?;
 
     // leave alternate screen and raw mode
     stdout().execute(LeaveAlternateScreen)?;
@@ -63,18 +147,37 @@ fn draw_terminal<B>(
 where
     B: Backend,
 {
+    let details = app.selected_details();
     terminal.draw(|f| {
+        // the details pane needs more room than the filter alone
+        let list_width = if app.details.is_some() { 55 } else { 80 };
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
+            .constraints(
+                [
+                    Constraint::Percentage(list_width),
+                    Constraint::Percentage(100 - list_width),
+                ]
+                .as_ref(),
+            )
             .split(f.area());
         let items: Vec<ListItem> = app
             .items
             .displayed
             .iter()
-            .map(|i| {
-                let lines = vec![Line::from(*i)];
-                ListItem::new(lines).style(Style::default())
+            .zip(&app.highlights)
+            .zip(&app.origins)
+            .map(|((i, highlighted), origin)| {
+                let mut line = highlight(i, highlighted);
+                if let (true, Some(index)) = (app.multi_select, origin) {
+                    let checkbox = if app.checked.contains(index) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    line.spans.insert(0, Span::raw(checkbox));
+                }
+                ListItem::new(vec![line]).style(Style::default())
             })
             .collect();
         let items = List::new(items)
@@ -85,26 +188,84 @@ where
 
         // if the user hasn't written anything yet, display the help message in its place
         let text = if app.filter.is_empty() {
+            let help = if app.multi_select {
+                "Press keys to filter, space to check an item and a or tab to check all"
+            } else {
+                "Press keys to filter"
+            };
             Paragraph::new(Span::styled(
-                "Press keys to filter",
+                help,
                 Style::default().add_modifier(Modifier::ITALIC),
             ))
             .wrap(Wrap { trim: true })
         } else {
             Paragraph::new(Span::raw(app.filter.clone())).wrap(Wrap { trim: true })
         };
-        f.render_widget(text, chunks[1]);
+        if app.details.is_some() {
+            // the filter on top and the details of the highlighted item below it
+            let side = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+                .split(chunks[1]);
+            f.render_widget(text, side[0]);
+            let details = Paragraph::new(details.unwrap_or_default())
+                .block(Block::default().borders(Borders::TOP).title("Details"))
+                .wrap(Wrap { trim: false });
+            f.render_widget(details, side[1]);
+        } else {
+            f.render_widget(text, chunks[1]);
+        }
     })?;
     Ok(())
 }
 
+/// styles the characters at the given char indices to show what matched the filter
+fn highlight<'a>(item: &'a str, highlighted: &[usize]) -> Line<'a> {
+    let highlight_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut previous_highlighted = false;
+    for (char_index, (byte_index, _)) in item.char_indices().enumerate() {
+        let is_highlighted = highlighted.contains(&char_index);
+        if is_highlighted != previous_highlighted && byte_index > start {
+            spans.push(styled_span(
+                &item[start..byte_index],
+                previous_highlighted,
+                highlight_style,
+            ));
+            start = byte_index;
+        }
+        previous_highlighted = is_highlighted;
+    }
+    spans.push(styled_span(
+        &item[start..],
+        previous_highlighted,
+        highlight_style,
+    ));
+    Line::from(spans)
+}
+
+fn styled_span(text: &str, highlighted: bool, highlight_style: Style) -> Span<'_> {
+    if highlighted {
+        Span::styled(text, highlight_style)
+    } else {
+        Span::raw(text)
+    }
+}
+
+/// what the user did in the prompt
+enum Action {
+    /// quit with ESC
+    Cancel,
+    /// pressed enter
+    Confirm,
+}
+
 /// tries reading input from user
-/// if succeeds, handles the input and returns Option<Option<String>> as return value
-///
-/// None: nothing was selected yet
-/// Some(None): the user selected nothing (quit with ESC)
-/// Some(Some(res)): the user selected an item
-fn read_keys(app: &mut AppState) -> anyhow::Result<Option<Option<String>>> {
+/// if succeeds, handles the input and returns the action that ends the prompt, if any
+fn read_keys(app: &mut AppState) -> anyhow::Result<Option<Action>> {
     if poll(Duration::from_millis(POLL_RATE))? {
         if let Event::Key(x) = read()? {
             // CTRL-C is the usual stop command
@@ -114,8 +275,8 @@ fn read_keys(app: &mut AppState) -> anyhow::Result<Option<Option<String>>> {
                 disable_raw_mode().ok();
                 std::process::exit(0);
             }
-            let selection = match x.code {
-                KeyCode::Esc => Some(None),
+            let action = match x.code {
+                KeyCode::Esc => Some(Action::Cancel),
                 KeyCode::Up | KeyCode::Left => {
                     app.items.previous();
                     None
@@ -124,9 +285,24 @@ fn read_keys(app: &mut AppState) -> anyhow::Result<Option<Option<String>>> {
                     app.items.next();
                     None
                 }
-                // if no selection, None
-                // else Some(Some(selection))
-                KeyCode::Enter => app.get_selected().map(Some),
+                KeyCode::Enter => Some(Action::Confirm),
+                KeyCode::Char(' ') if app.multi_select => {
+                    app.toggle_selected();
+                    None
+                }
+                KeyCode::Tab if app.multi_select => {
+                    app.toggle_all();
+                    None
+                }
+                KeyCode::Char('a') if app.multi_select && x.modifiers == KeyModifiers::CONTROL => {
+                    app.toggle_all();
+                    None
+                }
+                // a filter starting with a can be typed as A, the filter ignores case
+                KeyCode::Char('a') if app.multi_select && app.filter.is_empty() => {
+                    app.toggle_all();
+                    None
+                }
                 KeyCode::Char(c) => {
                     app.push_filter(c);
                     None
@@ -137,17 +313,18 @@ fn read_keys(app: &mut AppState) -> anyhow::Result<Option<Option<String>>> {
                 }
                 _ => None,
             };
-            return Ok(selection);
+            return Ok(action);
         }
     }
     Ok(None)
 }
 
-fn event_loop<B>(
+fn event_loop<B, T>(
     terminal: &mut Terminal<B>,
     app: &mut AppState<'_>,
     prompt: &str,
-) -> anyhow::Result<Option<String>>
+    selection: impl Fn(&AppState) -> Option<T>,
+) -> anyhow::Result<Option<T>>
 where
     B: Backend,
 {
@@ -155,11 +332,14 @@ where
         draw_terminal(terminal, app, prompt)?;
 
         match read_keys(app)? {
-            Some(Some(res)) => {
-                println!();
-                return Ok(Some(res));
+            Some(Action::Confirm) => {
+                // enter does nothing if nothing is selected
+                if let Some(res) = selection(app) {
+                    println!();
+                    return Ok(Some(res));
+                }
             }
-            Some(None) => {
+            Some(Action::Cancel) => {
                 // user interrupted selection
                 return Ok(None);
             }
@@ -1,4 +1,9 @@
+use super::fuzzy::fuzzy_match;
 use ratatui::widgets::ListState;
+use std::collections::BTreeSet;
+
+/// Gives the details of the item at an index, shown next to the list for the highlighted item
+pub type Details<'a> = &'a dyn Fn(usize) -> String;
 
 /// Handles the state of the application
 /// Provides functions `next`, `previous` etc.
@@ -74,26 +79,62 @@ impl<'a, T: Clone> StatefulList<'a, T> {
 /// let items = &["Eka", "Toka", "Kolmas"];
 /// let mut app = AppState::new(items);
 ///
-/// // no item is selected at first
-/// assert_eq!(None, app.get_selected());
-/// app.items.next();
+/// // the first item is selected at first
 /// assert_eq!(items[0], app.get_selected().unwrap());
 ///
-/// //filters out every item that doesn't contain 's'
+/// // filters out every item that doesn't contain 's'
 /// app.push_filter('s');
 /// assert_eq!(items[2], app.get_selected().unwrap());
 /// ```
 pub struct AppState<'a> {
     pub items: StatefulList<'a, &'a str>,
+    /// Items that are always displayed after the filtered items, e.g. "View all organizations"
+    pub pinned: &'a [&'a str],
+    /// Indices of the characters that matched the filter in each displayed item
+    pub highlights: Vec<Vec<usize>>,
+    /// Index of each displayed item in `items`, None for the pinned items
+    pub origins: Vec<Option<usize>>,
+    /// Whether items can be checked, see `interactive_multi_select`
+    pub multi_select: bool,
+    /// Indices of the items checked in a multi-select prompt
+    pub checked: BTreeSet<usize>,
+    pub details: Option<Details<'a>>,
     pub filter: String,
 }
 
 impl<'a> AppState<'a> {
     pub fn new(items: &'a [&'a str]) -> AppState<'a> {
-        let filter = String::from("");
-        let mut items = StatefulList::with_items(items);
-        items.next();
-        AppState { items, filter }
+        let mut app = AppState {
+            items: StatefulList::with_items(items),
+            pinned: &[],
+            highlights: Vec::new(),
+            origins: Vec::new(),
+            multi_select: false,
+            checked: BTreeSet::new(),
+            details: None,
+            filter: String::new(),
+        };
+        app.refresh_filtered();
+        app
+    }
+
+    /// sets the items that are displayed regardless of the filter
+    pub fn with_pinned(mut self, pinned: &'a [&'a str]) -> AppState<'a> {
+        self.pinned = pinned;
+        self.refresh_filtered();
+        self
+    }
+
+    /// shows the details of the highlighted item
+    pub fn with_details(mut self, details: Option<Details<'a>>) -> AppState<'a> {
+        self.details = details;
+        self
+    }
+
+    /// allows checking any number of items
+    pub fn multi_select(mut self) -> AppState<'a> {
+        self.multi_select = true;
+        self
     }
 
     /// pushes an ASCII character to the filter
@@ -122,17 +163,71 @@ impl<'a> AppState<'a> {
             .map(String::from)
     }
 
+    /// returns the details of the currently selected item,
+    /// or None if there is no selection, no details or the item is pinned
+    pub fn selected_details(&self) -> Option<String> {
+        let details = self.details?;
+        let index = self
+            .items
+            .state
+            .selected()
+            .and_then(|selected| self.origins.get(selected).copied().flatten())?;
+        Some(details(index))
+    }
+
+    /// checks the currently selected item, or unchecks it if it is already checked
+    pub fn toggle_selected(&mut self) {
+        let origin = self
+            .items
+            .state
+            .selected()
+            .and_then(|selected| self.origins.get(selected).copied().flatten());
+        if let Some(index) = origin {
+            if !self.checked.remove(&index) {
+                self.checked.insert(index);
+            }
+        }
+    }
+
+    /// checks every displayed item, or unchecks them if they are all checked already
+    pub fn toggle_all(&mut self) {
+        let displayed = self.origins.iter().flatten().copied().collect::<Vec<_>>();
+        if displayed.iter().all(|index| self.checked.contains(index)) {
+            for index in displayed {
+                self.checked.remove(&index);
+            }
+        } else {
+            self.checked.extend(displayed);
+        }
+    }
+
+    /// displays the items that match the filter, best matches first, followed by the pinned items
     fn refresh_filtered(&mut self) {
-        self.items.displayed = self
+        let mut matches = self
             .items
             .items
             .iter()
-            .filter(|item| {
-                item.to_lowercase().contains(&self.filter.to_lowercase())
-                    || item.to_lowercase().contains("view all")
+            .enumerate()
+            .filter_map(|(index, item)| {
+                fuzzy_match(&self.filter, item).map(|found| (index, *item, found))
             })
-            .cloned()
-            .collect();
+            .collect::<Vec<_>>();
+        // the sort is stable, so equally good matches keep their original order
+        matches.sort_by_key(|(_, _, found)| std::cmp::Reverse(found.score));
+
+        self.items.displayed.clear();
+        self.highlights.clear();
+        self.origins.clear();
+        for (index, item, found) in matches {
+            self.items.displayed.push(item);
+            self.highlights.push(found.indices);
+            self.origins.push(Some(index));
+        }
+        for item in self.pinned {
+            self.items.displayed.push(*item);
+            self.highlights.push(Vec::new());
+            self.origins.push(None);
+        }
 
         self.items.state = ListState::default();
         self.items.next();
@@ -183,4 +278,93 @@ mod tests {
         app.pop_filter();
         assert_eq!(items[0], app.get_selected().unwrap());
     }
+
+    #[test]
+    fn app_filter_ranks_matches() {
+        let items: &[&str] = &[
+            "Data Analysis with Python",
+            "Ohjelmoinnin MOOC 2020",
+            "Ohjelmoinnin MOOC 2021",
+            "Python Programming MOOC",
+        ];
+
+        let mut app = AppState::new(items);
+        for c in "py".chars() {
+            app.push_filter(c);
+        }
+        assert_eq!(
+            app.items.displayed,
+            ["Python Programming MOOC", "Data Analysis with Python"]
+        );
+        assert_eq!(app.highlights, [vec![0, 1], vec![19, 20]]);
+
+        let mut app = AppState::new(items);
+        for c in "ohjm21".chars() {
+            app.push_filter(c);
+        }
+        assert_eq!(app.items.displayed, ["Ohjelmoinnin MOOC 2021"]);
+    }
+
+    #[test]
+    fn app_pinned_items_are_always_displayed() {
+        let items: &[&str] = &["eka", "toka", "kolmas"];
+        let pinned: &[&str] = &["View all"];
+
+        let mut app = AppState::new(items).with_pinned(pinned);
+        assert_eq!(app.items.displayed, ["eka", "toka", "kolmas", "View all"]);
+
+        app.push_filter('s');
+        assert_eq!(app.items.displayed, ["kolmas", "View all"]);
+        app.push_filter('x');
+        assert_eq!(app.items.displayed, ["View all"]);
+        assert_eq!(app.get_selected().unwrap(), "View all");
+    }
+
+    #[test]
+    fn app_toggle_checked_items() {
+        let items = get_item_list();
+        let pinned: &[&str] = &["View all"];
+
+        let mut app = AppState::new(items).with_pinned(pinned);
+        app.toggle_selected();
+        app.items.next();
+        app.items.next();
+        app.toggle_selected();
+        assert_eq!(app.checked.iter().copied().collect::<Vec<_>>(), [0, 2]);
+        app.toggle_selected();
+        assert_eq!(app.checked.iter().copied().collect::<Vec<_>>(), [0]);
+
+        // pinned items can't be checked
+        app.items.next();
+        app.toggle_selected();
+        assert_eq!(app.checked.iter().copied().collect::<Vec<_>>(), [0]);
+
+        // toggling all only affects the displayed items
+        app.push_filter('k');
+        app.toggle_all();
+        assert_eq!(app.checked.iter().copied().collect::<Vec<_>>(), [0, 1, 2]);
+        app.pop_filter();
+        app.push_filter('t');
+        app.toggle_all();
+        assert_eq!(app.checked.iter().copied().collect::<Vec<_>>(), [0, 2]);
+    }
+
+    #[test]
+    fn app_selected_details() {
+        let items = get_item_list();
+        let pinned: &[&str] = &["View all"];
+        let details = |index: usize| format!("details of {}", items[index]);
+
+        let mut app = AppState::new(items)
+            .with_pinned(pinned)
+            .with_details(Some(&details));
+        assert_eq!(app.selected_details().unwrap(), "details of eka");
+        app.push_filter('l');
+        assert_eq!(app.selected_details().unwrap(), "details of kolmas");
+        app.items.next();
+        assert_eq!(app.selected_details(), None);
+
+        let app = AppState::new(items);
+        assert_eq!(app.selected_details(), None);
+    }
 }
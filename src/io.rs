@@ -1,15 +1,25 @@
-use std::io::{BufRead, BufReader, Read};
+use crate::cli::OutputFormat;
+use crossterm::{
+    cursor::MoveTo,
+    terminal::{Clear, ClearType},
+};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
 use termcolor::{Color, ColorSpec, WriteColor};
 
 pub enum PrintColor {
     Success,
     Normal,
+    Warning,
     Failed,
 }
 
 pub struct Io<'a> {
     output: &'a mut dyn WriteColor,
     input: BufReader<&'a mut dyn Read>,
+    format: OutputFormat,
+    /// Whether the command has printed its result as JSON
+    json_printed: bool,
 }
 
 /// Example use:
@@ -36,7 +46,25 @@ io.print(&x, PrintColor::Normal)?;
 impl Io<'_> {
     pub fn new<'a>(output: &'a mut dyn WriteColor, input: &'a mut dyn Read) -> Io<'a> {
         let input = BufReader::new(input);
-        Io { output, input }
+        Io {
+            output,
+            input,
+            format: OutputFormat::Text,
+            json_printed: false,
+        }
+    }
+
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Whether a JSON document has been printed to stdout
+    pub fn json_printed(&self) -> bool {
+        self.json_printed
     }
 
     pub fn read_line(&mut self) -> anyhow::Result<String> {
@@ -60,6 +88,16 @@ impl Io<'_> {
             PrintColor::Normal => {
                 self.output.write_all(text_to_output.as_bytes())?;
             }
+            PrintColor::Warning => {
+                let mut colorspec = ColorSpec::new();
+                colorspec.set_fg(Some(Color::Yellow)).set_bold(true);
+                self.output.set_color(&colorspec)?;
+
+                self.output.write_all(text_to_output.as_bytes())?;
+
+                colorspec.clear();
+                self.output.set_color(&colorspec)?;
+            }
             PrintColor::Failed => {
                 let mut colorspec = ColorSpec::new();
                 colorspec.set_fg(Some(Color::Red)).set_bold(true);
@@ -80,6 +118,24 @@ impl Io<'_> {
         Ok(())
     }
 
+    /// Clears the terminal and moves the cursor to the top left corner
+    pub fn clear_screen(&mut self) -> anyhow::Result<()> {
+        crossterm::execute!(&mut self.output, Clear(ClearType::All), MoveTo(0, 0))?;
+        Ok(())
+    }
+
+    /// Prints the value as a single line of JSON to stdout if the JSON output format is in use,
+    /// does nothing otherwise. The human-readable output keeps going to the regular output.
+    pub fn print_json<T: Serialize + ?Sized>(&mut self, value: &T) -> anyhow::Result<()> {
+        if self.is_json() {
+            let mut stdout = std::io::stdout().lock();
+            serde_json::to_writer(&mut stdout, value)?;
+            writeln!(stdout)?;
+            self.json_printed = true;
+        }
+        Ok(())
+    }
+
     pub fn read_password(&mut self) -> anyhow::Result<String> {
         rpassword::read_password().map_err(Into::into)
     }
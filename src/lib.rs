@@ -1,7 +1,9 @@
+mod cache;
 mod cli;
 mod client;
 mod commands;
 mod config;
+mod error;
 mod interactive;
 mod io;
 mod progress_reporting;
@@ -16,20 +18,31 @@ mod updater;
 pub use cli::Cli;
 use config::TmcCliConfig;
 pub use io::{Io, PrintColor};
+use std::process::ExitCode;
 
 pub const PLUGIN: &str = "tmc_cli_rust";
 pub const PLUGIN_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub fn run(cli: Cli, io: &mut Io) {
+pub fn run(cli: Cli, io: &mut Io) -> ExitCode {
+    io.set_format(cli.format);
     if let Err(err) = run_inner(io, cli) {
         let error_string = format!("{err:#}");
         log::error!("{error_string}");
+        // a command that failed after printing its result, e.g. a submission whose tests failed,
+        // has already described the failure in its JSON document
+        if !io.json_printed() {
+            if let Err(err) = io.print_json(&serde_json::json!({ "error": error_string })) {
+                log::error!("Failed to print error as JSON: {err}");
+            }
+        }
         if let Err(err) = io.println(&error_string, PrintColor::Failed) {
             println!(
                 "Failed to print error due to error {err}\nThe underlying error was\n{error_string}"
             );
         }
+        return ExitCode::from(error::exit_code(&err));
     }
+    ExitCode::SUCCESS
 }
 
 fn run_inner(io: &mut Io, cli: Cli) -> anyhow::Result<()> {
@@ -45,7 +58,8 @@ fn run_inner(io: &mut Io, cli: Cli) -> anyhow::Result<()> {
             #[cfg(target_os = "windows")]
             updater::check_for_update(&mut config, cli.force_update)?;
         }
-    } else {
+    } else if !io.is_json() {
+        // stdout is reserved for the JSON document in JSON mode
         println!("No Auto-Updates");
     }
 
@@ -1,13 +1,13 @@
 #![deny(clippy::unwrap_used, clippy::panic, clippy::todo)]
 
 use clap::Parser;
-use std::fs::File;
+use std::{fs::File, process::ExitCode};
 use termcolor::{ColorChoice, StandardStream};
 use tmc::{Cli, Io};
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
-fn main() {
+fn main() -> ExitCode {
     let cli = Cli::parse();
     let mut stdin = std::io::stdin();
     let color = if cli.testmode {
@@ -51,5 +51,5 @@ fn main() {
         }
     }
     let mut io = Io::new(&mut output, &mut stdin);
-    tmc::run(cli, &mut io);
+    tmc::run(cli, &mut io)
 }
@@ -16,6 +16,18 @@ pub fn get_default_style() -> ProgressStyle {
         .progress_chars("██░")
 }
 
+/// A spinner for waiting on something that does not report its progress.
+/// Hidden in test mode so that it does not end up in the output of the integration tests.
+pub fn spinner(message: &str, is_test_mode: bool) -> ProgressBar {
+    if is_test_mode {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_message(message.to_string());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
 pub struct ProgressBarManager {
     is_test_mode: bool,
     style: ProgressStyle,
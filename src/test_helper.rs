@@ -35,7 +35,12 @@ pub fn setup<'a>(
     let _config = TmcCliConfig::load(config_file.path().to_path_buf()).unwrap();
     TestSetup {
         io: Io::new(output, input),
-        client: Client::new(server.url().parse().unwrap(), false).unwrap(),
+        client: Client::new(
+            server.url().parse().unwrap(),
+            crate::PLUGIN.to_string(),
+            false,
+        )
+        .unwrap(),
     }
 }
 
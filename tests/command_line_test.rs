@@ -1,6 +1,10 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 const PKG_NAME: &str = env!("CARGO_PKG_NAME");
 
@@ -52,14 +56,28 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
             testpassword
             imagorganization -n
             testcourses
+        --format json courses
+        --format json test --all --report junit
         exercises test-tmc-test-course
         download -c test-tmc-test-course -f folder_for_download
+        submit <exercise>
+        submit --passing
+        submissions -c test-tmc-test-course
+        --format json submissions -c test-tmc-test-course
         test folder/nonexistant_ex
         logout
         login
             totallywrongname
             cantrememberpasswordeither
             imag
+        login --username testusername --password-stdin --organization imag
+            testpassword
+        logout
+        login --token-stdin --organization imag
+            testtoken
+        whoami
+        logout
+        whoami
     */
 
     let config_dir = tempfile::tempdir().unwrap();
@@ -71,6 +89,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         config_dir.path().as_os_str().to_str().unwrap().to_string(),
     );
     envs.insert("TMC_LANGS_ROOT_URL", server.url());
+    envs.insert("TMC_LANGS_TMC_ROOT_URL", server.url());
     // envs.insert("RUST_LOG", "debug".to_string());
 
     // logout
@@ -84,7 +103,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         .arg("download")
         .arg("-c")
         .arg("test-tmc-test-course");
-    cmd.assert().success().stderr(predicate::str::contains(
+    cmd.assert().code(3).stderr(predicate::str::contains(
         "No login found. Login to use this command with 'tmc login'",
     ));
 
@@ -93,7 +112,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
     cmd.arg("--testmode")
         .arg("courses")
         .assert()
-        .success()
+        .code(3)
         .stderr(predicate::str::contains(
             "No login found. Login to use this command with 'tmc login'",
         ));
@@ -103,7 +122,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
     cmd.arg("--testmode")
         .arg("submit")
         .assert()
-        .success()
+        .code(3)
         .stderr(predicate::str::contains(
             "No login found. Login to use this command with 'tmc login'",
         ));
@@ -113,7 +132,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
     cmd.arg("--testmode")
         .arg("paste")
         .assert()
-        .success()
+        .code(3)
         .stderr(predicate::str::contains(
             "No login found. Login to use this command with 'tmc login'",
         ));
@@ -123,7 +142,7 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
     cmd.arg("--testmode")
         .arg("exercises")
         .arg("test-tmc-test-course");
-    cmd.assert().success().stderr(predicate::str::contains(
+    cmd.assert().code(3).stderr(predicate::str::contains(
         "No login found. Login to use this command with 'tmc login'",
     ));
 
@@ -151,6 +170,28 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
             "Selected test organization as organization.",
         ));
 
+    // organization without a terminal, chosen from the numbered list
+    // 1 (View all organizations), test organization
+    let mut cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("organization")
+        .write_stdin("1\ntest organization\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1) View all organizations"))
+        .stderr(predicate::str::contains(
+            "Selected test organization as organization.",
+        ));
+
+    // the numbered list is cancelled by an empty line
+    let mut cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("organization")
+        .write_stdin("\n")
+        .assert()
+        .code(10)
+        .stderr(predicate::str::contains("Didn't select any organization"));
+
     // courses
     cmd = command(&envs);
     cmd.arg("--testmode").arg("courses");
@@ -158,6 +199,50 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         .success()
         .stderr(predicate::str::contains("test-tmc-test-course"));
 
+    // --format json courses
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("--format")
+        .arg("json")
+        .arg("courses");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("["))
+        .stdout(predicate::str::contains(
+            "\"name\":\"test-tmc-test-course\"",
+        ));
+
+    // --format json test --all --report junit, the report would mix with the JSON output
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("--format")
+        .arg("json")
+        .arg("test")
+        .arg("--all")
+        .arg("--report")
+        .arg("junit");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("can't be written to stdout"));
+
+    // --offline courses
+    cmd = command(&envs);
+    cmd.arg("--testmode").arg("--offline").arg("courses");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("test-tmc-test-course"))
+        .stderr(predicate::str::contains("Offline: showing cached data"));
+
+    // --offline exercises test-tmc-test-course
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("--offline")
+        .arg("exercises")
+        .arg("test-tmc-test-course");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No cached data"));
+
     // exercises test-tmc-test-course
     cmd = command(&envs);
     cmd.arg("--testmode")
@@ -167,6 +252,26 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         .success()
         .stderr(predicate::str::contains("Imaginary test exercise"));
 
+    // exercises "test tmc test course"
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("exercises")
+        .arg("test tmc test course");
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Course name: test-tmc-test-course",
+    ));
+
+    // download -c test-tmc-tset-course
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("download")
+        .arg("-c")
+        .arg("test-tmc-tset-course");
+    cmd.assert()
+        .code(6)
+        .stderr(predicate::str::contains("Did you mean:"))
+        .stderr(predicate::str::contains("test-tmc-test-course"));
+
     // download -c test-tmc-test-course -f folder_for_download
     cmd = command(&envs);
     cmd.arg("--testmode")
@@ -177,13 +282,90 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         .success()
         .stderr(predicate::str::contains("Download was successful!"));
 
+    // download -c test-tmc-test-course --exercise "Imaginary*"
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("download")
+        .arg("-c")
+        .arg("test-tmc-test-course")
+        .arg("--exercise")
+        .arg("Imaginary*");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Selected 1 exercises:\n  Imaginary test exercise",
+        ))
+        .stderr(predicate::str::contains("Download was successful!"));
+
+    // download -c test-tmc-test-course --part 1
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("download")
+        .arg("-c")
+        .arg("test-tmc-test-course")
+        .arg("--part")
+        .arg("1");
+    cmd.assert().code(6).stderr(predicate::str::contains(
+        "No exercises of course 'test-tmc-test-course' match the given filters",
+    ));
+
+    // submit <exercise>
+    let projects_dir = tempfile::tempdir().unwrap();
+    let exercise_dir = projects_exercise(projects_dir.path());
+    cmd = command(&envs);
+    cmd.arg("--testmode").arg("submit").arg(&exercise_dir);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("All tests passed on server!"));
+
+    // submit --passing in the course directory
+    let course_dir = exercise_dir.parent().unwrap();
+    cmd = command(&envs);
+    cmd.current_dir(course_dir)
+        .arg("--testmode")
+        .arg("submit")
+        .arg("--passing");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Submitting part01-01_hello"))
+        .stderr(predicate::str::contains("All tests passed"));
+
+    // submissions -c test-tmc-test-course
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("submissions")
+        .arg("-c")
+        .arg("test-tmc-test-course");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Course: test-tmc-test-course"))
+        .stderr(predicate::str::contains("part01-01_hello  Passed"))
+        .stderr(predicate::str::contains("points: 1.1, 1.2"))
+        .stderr(predicate::str::contains("/submissions/7400888"));
+
+    // --format json submissions -c test-tmc-test-course
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("--format")
+        .arg("json")
+        .arg("submissions")
+        .arg("-c")
+        .arg("test-tmc-test-course");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("["))
+        .stdout(predicate::str::contains("\"exercise\":\"part01-01_hello\""))
+        .stdout(predicate::str::contains("\"created_at\":\"2021-01-01T"))
+        .stdout(predicate::str::contains("\"status\":\"Passed\""))
+        .stdout(predicate::str::contains("\"points\":[\"1.1\",\"1.2\"]"));
+
     // test folder/nonexistant_ex
     cmd = command(&envs);
     cmd.arg("--testmode")
         .arg("test")
         .arg("folder/nonexistant_ex");
     cmd.assert()
-        .success()
+        .code(1)
         .stderr(predicate::str::contains("Failed to load projects config"));
 
     // logout
@@ -200,12 +382,83 @@ fn all_integration_tests() -> Result<(), Box<dyn std::error::Error>> {
         .arg("login")
         .write_stdin("totallywrongname\ncantrememberpasswordeither\nimag\n")
         .assert()
-        .success()
+        .code(4)
         .stderr(predicate::str::contains("Wrong username or password"));
 
+    // login --username testusername --password-stdin --organization imag
+    // testpassword
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("login")
+        .arg("--username")
+        .arg("testusername")
+        .arg("--password-stdin")
+        .arg("--organization")
+        .arg("imag")
+        .write_stdin("testpassword\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Logged in successfully!"))
+        .stderr(predicate::str::contains("Select your organization").not());
+
+    // logout
+    cmd = command(&envs);
+    cmd.arg("--testmode").arg("logout").assert().success();
+
+    // login --token-stdin --organization imag
+    // testtoken
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("login")
+        .arg("--token-stdin")
+        .arg("--organization")
+        .arg("imag")
+        .write_stdin("testtoken\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Logged in successfully!"));
+
+    // whoami
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("whoami")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "testusername (testusername@example.com)",
+        ))
+        .stderr(predicate::str::contains("imag"));
+
+    // logout
+    cmd = command(&envs);
+    cmd.arg("--testmode").arg("logout").assert().success();
+
+    // whoami
+    cmd = command(&envs);
+    cmd.arg("--testmode")
+        .arg("whoami")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("No login found"));
+
     Ok(())
 }
 
+/// Creates a downloaded course with a single exercise that has no tests,
+/// returns the path of the exercise
+fn projects_exercise(projects_dir: &Path) -> PathBuf {
+    let course_dir = projects_dir.join("test-tmc-test-course");
+    let exercise_dir = course_dir.join("part01-01_hello");
+    fs::create_dir_all(&exercise_dir).unwrap();
+    fs::write(
+        course_dir.join("course_config.toml"),
+        "course = \"test-tmc-test-course\"\n\n[exercises.part01-01_hello]\nid = 1\nchecksum = \"abc\"\n",
+    )
+    .unwrap();
+    fs::write(exercise_dir.join(".tmcproject.yml"), "no-tests: true\n").unwrap();
+    exercise_dir
+}
+
 fn command(envs: &HashMap<&str, String>) -> Command {
     let mut command = Command::cargo_bin(PKG_NAME).unwrap();
     command.envs(envs);